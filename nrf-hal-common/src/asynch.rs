@@ -0,0 +1,67 @@
+//! Helpers shared by the async drivers.
+
+use core::cell::RefCell;
+use core::task::Waker;
+
+use cortex_m::interrupt::{self, Mutex};
+
+/// Storage for the waker of the task awaiting a peripheral event.
+///
+/// Registered from a future's `poll` and woken from the peripheral's interrupt
+/// handler.
+pub struct AtomicWaker {
+    waker: Mutex<RefCell<Option<Waker>>>,
+}
+
+impl AtomicWaker {
+    pub const fn new() -> Self {
+        Self {
+            waker: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Registers `waker` to be woken by the next call to `wake`.
+    pub fn register(&self, waker: &Waker) {
+        interrupt::free(|cs| {
+            let mut slot = self.waker.borrow(cs).borrow_mut();
+            match slot.as_ref() {
+                Some(w) if w.will_wake(waker) => {}
+                _ => *slot = Some(waker.clone()),
+            }
+        })
+    }
+
+    /// Wakes the registered task, if any.
+    pub fn wake(&self) {
+        if let Some(waker) = interrupt::free(|cs| self.waker.borrow(cs).borrow_mut().take()) {
+            waker.wake();
+        }
+    }
+}
+
+/// Runs a closure when dropped, unless defused.
+///
+/// Async drivers use this to stop an ongoing EasyDMA transfer when the future
+/// driving it is dropped before completion.
+pub struct OnDrop<F: FnOnce()> {
+    f: Option<F>,
+}
+
+impl<F: FnOnce()> OnDrop<F> {
+    pub fn new(f: F) -> Self {
+        Self { f: Some(f) }
+    }
+
+    /// Prevents the closure from running.
+    pub fn defuse(mut self) {
+        self.f = None;
+    }
+}
+
+impl<F: FnOnce()> Drop for OnDrop<F> {
+    fn drop(&mut self) {
+        if let Some(f) = self.f.take() {
+            f()
+        }
+    }
+}
@@ -0,0 +1,499 @@
+//! Minimal BLE link layer: non-connectable and scannable advertising, and passive scanning.
+//!
+//! This runs on top of [`radio::Radio`](crate::radio::Radio) and does not need a SoftDevice.
+//! Only legacy advertising on the primary advertising channels 37, 38 and 39 is supported.
+//! Connections are not supported, so `CONNECT_IND` PDUs received by a connectable advertiser are
+//! ignored.
+
+use core::sync::atomic::{compiler_fence, Ordering};
+
+use embedded_hal::timer::CountDown as _;
+
+use crate::{
+    radio::{self, CrcConfig, Mode, PacketConfig, Radio},
+    timer::{self, Timer},
+};
+
+/// Access address of all advertising channel packets.
+pub const ADVERTISING_ACCESS_ADDRESS: u32 = 0x8E89_BED6;
+
+/// CRC initial value of all advertising channel packets.
+pub const ADVERTISING_CRC_INIT: u32 = 0x55_5555;
+
+/// Maximum length of an advertising channel PDU payload.
+const MAX_PAYLOAD_LEN: usize = 37;
+
+/// Maximum length of the advertising or scan response data.
+pub const MAX_DATA_LEN: usize = 31;
+
+/// Size of an advertising channel PDU in RAM: header, length and payload.
+const PDU_SIZE: usize = 2 + MAX_PAYLOAD_LEN;
+
+/// Time between the end of a packet and the start of the next one (T_IFS), in microseconds.
+const T_IFS: u32 = 150;
+
+/// How long to wait for the address of a `SCAN_REQ` after an advertising PDU, in microseconds.
+///
+/// T_IFS, plus 40 µs for the preamble and access address at 1 Mbit/s and some tolerance.
+const SCAN_REQ_TIMEOUT: u32 = T_IFS + 40 + 20;
+
+/// A primary advertising channel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AdvertisingChannel {
+    /// 2402 MHz
+    _37,
+    /// 2426 MHz
+    _38,
+    /// 2480 MHz
+    _39,
+}
+
+impl AdvertisingChannel {
+    /// All primary advertising channels, in the order they are used.
+    pub const ALL: [AdvertisingChannel; 3] = [
+        AdvertisingChannel::_37,
+        AdvertisingChannel::_38,
+        AdvertisingChannel::_39,
+    ];
+
+    /// Returns the channel index, which is also the data whitening initial value.
+    pub fn index(self) -> u8 {
+        match self {
+            AdvertisingChannel::_37 => 37,
+            AdvertisingChannel::_38 => 38,
+            AdvertisingChannel::_39 => 39,
+        }
+    }
+
+    /// Returns the channel frequency in MHz.
+    pub fn frequency(self) -> u16 {
+        match self {
+            AdvertisingChannel::_37 => 2402,
+            AdvertisingChannel::_38 => 2426,
+            AdvertisingChannel::_39 => 2480,
+        }
+    }
+}
+
+/// A BLE device address.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviceAddress {
+    /// The address bytes, least significant byte first (the order used on air)
+    pub bytes: [u8; 6],
+    /// `true` for a random address, `false` for a public address
+    pub random: bool,
+}
+
+/// Advertising channel PDU type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PduType {
+    AdvInd,
+    AdvDirectInd,
+    AdvNonconnInd,
+    ScanReq,
+    ScanRsp,
+    ConnectInd,
+    AdvScanInd,
+    /// A reserved PDU type
+    Reserved(u8),
+}
+
+impl PduType {
+    fn from_bits(bits: u8) -> Self {
+        match bits & 0x0F {
+            0 => PduType::AdvInd,
+            1 => PduType::AdvDirectInd,
+            2 => PduType::AdvNonconnInd,
+            3 => PduType::ScanReq,
+            4 => PduType::ScanRsp,
+            5 => PduType::ConnectInd,
+            6 => PduType::AdvScanInd,
+            other => PduType::Reserved(other),
+        }
+    }
+
+    fn bits(self) -> u8 {
+        match self {
+            PduType::AdvInd => 0,
+            PduType::AdvDirectInd => 1,
+            PduType::AdvNonconnInd => 2,
+            PduType::ScanReq => 3,
+            PduType::ScanRsp => 4,
+            PduType::ConnectInd => 5,
+            PduType::AdvScanInd => 6,
+            PduType::Reserved(bits) => bits & 0x0F,
+        }
+    }
+}
+
+/// Kind of advertising.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AdvertisingKind {
+    /// `ADV_NONCONN_IND`: not scannable, not connectable
+    NonConnectable,
+    /// `ADV_SCAN_IND`: answers scan requests with the scan response data
+    Scannable,
+    /// `ADV_IND`: answers scan requests with the scan response data; connection requests are
+    /// ignored
+    Connectable,
+}
+
+/// Configures `radio` for advertising channel packets.
+fn configure(radio: &mut Radio<'_>) {
+    radio.set_mode(Mode::Ble1Mbit);
+    radio.set_packet_config(&PacketConfig {
+        max_len: MAX_PAYLOAD_LEN as u8,
+        ..PacketConfig::default()
+    });
+    radio.set_crc(CrcConfig::ble(ADVERTISING_CRC_INIT));
+
+    // The 3 most significant bytes of BASE0 are used as base address
+    radio.set_base_address(0, ADVERTISING_ACCESS_ADDRESS << 8);
+    radio.set_prefix(0, (ADVERTISING_ACCESS_ADDRESS >> 24) as u8);
+    radio.set_tx_address(0);
+    radio.set_rx_addresses(1 << 0);
+}
+
+fn set_channel(radio: &mut Radio<'_>, channel: AdvertisingChannel) {
+    radio.set_frequency(channel.frequency());
+    radio.set_whitening_iv(channel.index());
+}
+
+/// Writes a PDU with the header for `pdu_type` and the payload `address` + `data` into `buffer`.
+fn write_pdu(
+    buffer: &mut [u8; PDU_SIZE],
+    pdu_type: PduType,
+    address: &DeviceAddress,
+    data: &[u8],
+) -> Result<(), Error> {
+    if data.len() > MAX_DATA_LEN {
+        return Err(Error::DataTooLong);
+    }
+    // TxAdd is bit 6 of the header
+    buffer[0] = pdu_type.bits() | u8::from(address.random) << 6;
+    buffer[1] = (6 + data.len()) as u8;
+    buffer[2..8].copy_from_slice(&address.bytes);
+    buffer[8..8 + data.len()].copy_from_slice(data);
+    Ok(())
+}
+
+/// A BLE advertiser.
+pub struct Advertiser<'c> {
+    radio: Radio<'c>,
+    address: DeviceAddress,
+    adv_pdu: [u8; PDU_SIZE],
+    scan_rsp_pdu: [u8; PDU_SIZE],
+    rx_pdu: [u8; PDU_SIZE],
+    kind: AdvertisingKind,
+}
+
+impl<'c> Advertiser<'c> {
+    /// Creates an advertiser of `kind`, using the device `address`, with empty advertising and
+    /// scan response data.
+    pub fn new(radio: Radio<'c>, address: DeviceAddress, kind: AdvertisingKind) -> Self {
+        let mut advertiser = Self {
+            radio,
+            address,
+            adv_pdu: [0; PDU_SIZE],
+            scan_rsp_pdu: [0; PDU_SIZE],
+            rx_pdu: [0; PDU_SIZE],
+            kind,
+        };
+        configure(&mut advertiser.radio);
+        // Neither can fail with empty data
+        let _ = advertiser.set_data(&[]);
+        let _ = advertiser.set_scan_response(&[]);
+        advertiser
+    }
+
+    /// Sets the advertising data, at most 31 bytes of AD structures.
+    pub fn set_data(&mut self, data: &[u8]) -> Result<(), Error> {
+        let pdu_type = match self.kind {
+            AdvertisingKind::NonConnectable => PduType::AdvNonconnInd,
+            AdvertisingKind::Scannable => PduType::AdvScanInd,
+            AdvertisingKind::Connectable => PduType::AdvInd,
+        };
+        write_pdu(&mut self.adv_pdu, pdu_type, &self.address, data)
+    }
+
+    /// Sets the scan response data, at most 31 bytes of AD structures.
+    ///
+    /// Only sent by scannable and connectable advertisers.
+    pub fn set_scan_response(&mut self, data: &[u8]) -> Result<(), Error> {
+        write_pdu(
+            &mut self.scan_rsp_pdu,
+            PduType::ScanRsp,
+            &self.address,
+            data,
+        )
+    }
+
+    /// Sets the TX power.
+    pub fn set_txpower(&mut self, power: radio::TxPower) {
+        self.radio.set_txpower(power);
+    }
+
+    /// Runs one advertising event, sending the advertising PDU on channels 37, 38 and 39.
+    ///
+    /// Scannable and connectable advertisers listen for a `SCAN_REQ` after each PDU and answer
+    /// it with the scan response, using `timer` to time out. Returns the number of scan
+    /// requests that have been answered.
+    ///
+    /// The advertising interval (at least 20 ms plus a random delay of up to 10 ms) between
+    /// events is up to the caller.
+    pub fn advertise<I>(&mut self, timer: &mut Timer<I>) -> Result<u8, Error>
+    where
+        I: timer::Instance,
+    {
+        let mut answered = 0;
+        for &channel in AdvertisingChannel::ALL.iter() {
+            if self.advertise_on(channel, timer)? {
+                answered += 1;
+            }
+        }
+        Ok(answered)
+    }
+
+    /// Sends the advertising PDU on a single `channel`, see `advertise`.
+    ///
+    /// Returns `true` if a scan request has been answered.
+    pub fn advertise_on<I>(
+        &mut self,
+        channel: AdvertisingChannel,
+        timer: &mut Timer<I>,
+    ) -> Result<bool, Error>
+    where
+        I: timer::Instance,
+    {
+        set_channel(&mut self.radio, channel);
+
+        if self.kind == AdvertisingKind::NonConnectable {
+            self.radio.send(&self.adv_pdu)?;
+            return Ok(false);
+        }
+
+        let rx_ptr = self.rx_pdu.as_mut_ptr();
+        let radio = self.radio.regs();
+
+        radio.events_address.reset();
+        radio.events_end.reset();
+        radio.events_disabled.reset();
+        radio.tifs.write(|w| unsafe { w.bits(T_IFS) });
+        radio
+            .packetptr
+            .write(|w| unsafe { w.bits(self.adv_pdu.as_ptr() as u32) });
+
+        // Turn around to receive T_IFS after the advertising PDU.
+        radio.shorts.write(|w| {
+            w.ready_start()
+                .enabled()
+                .end_disable()
+                .enabled()
+                .disabled_rxen()
+                .enabled()
+        });
+
+        compiler_fence(Ordering::Release);
+        radio.tasks_txen.write(|w| unsafe { w.bits(1) });
+
+        while radio.events_disabled.read().bits() == 0 {}
+        // The transmission raised ADDRESS and END as well; clear them, so that only the
+        // events of the reception are waited for below.
+        radio.events_address.reset();
+        radio.events_end.reset();
+        radio.events_disabled.reset();
+
+        // The receiver is ramping up: receive into `rx_pdu`, and arm the turnaround to
+        // transmit T_IFS after the reception, in case it is a scan request.
+        radio.packetptr.write(|w| unsafe { w.bits(rx_ptr as u32) });
+        radio.shorts.write(|w| {
+            w.ready_start()
+                .enabled()
+                .end_disable()
+                .enabled()
+                .disabled_txen()
+                .enabled()
+        });
+
+        timer.start(SCAN_REQ_TIMEOUT);
+        while radio.events_address.read().bits() == 0 {
+            if timer.wait().is_ok() {
+                self.stop();
+                return Ok(false);
+            }
+        }
+
+        // Wait for the end of the reception; the radio then turns around to TX.
+        while radio.events_disabled.read().bits() == 0 {}
+        radio.events_disabled.reset();
+        compiler_fence(Ordering::Acquire);
+
+        let radio = self.radio.regs();
+        let crc_ok = radio.crcstatus.read().crcstatus().bit_is_set();
+        if !(crc_ok && self.is_scan_request()) {
+            self.stop();
+            return Ok(false);
+        }
+
+        // Answer with the scan response; the transmitter starts once it has ramped up.
+        radio
+            .packetptr
+            .write(|w| unsafe { w.bits(self.scan_rsp_pdu.as_ptr() as u32) });
+        radio
+            .shorts
+            .write(|w| w.ready_start().enabled().end_disable().enabled());
+        compiler_fence(Ordering::Release);
+
+        while radio.events_disabled.read().bits() == 0 {}
+        radio.events_disabled.reset();
+        radio.shorts.reset();
+
+        Ok(true)
+    }
+
+    /// Returns `true` if `rx_pdu` holds a `SCAN_REQ` addressed to this advertiser.
+    fn is_scan_request(&self) -> bool {
+        let header = self.rx_pdu[0];
+        // RxAdd is bit 7 of the header
+        PduType::from_bits(header) == PduType::ScanReq
+            && self.rx_pdu[1] == 12
+            && (header & 0x80 != 0) == self.address.random
+            && self.rx_pdu[8..14] == self.address.bytes
+    }
+
+    /// Disables the radio, cancelling a reception or a pending turnaround.
+    fn stop(&mut self) {
+        let radio = self.radio.regs();
+        radio.shorts.reset();
+        radio.events_disabled.reset();
+        radio.tasks_disable.write(|w| unsafe { w.bits(1) });
+        while radio.events_disabled.read().bits() == 0 {}
+        radio.events_disabled.reset();
+        compiler_fence(Ordering::Acquire);
+    }
+
+    /// Returns the radio.
+    pub fn free(self) -> Radio<'c> {
+        self.radio
+    }
+}
+
+/// A received advertising channel PDU.
+#[derive(Debug)]
+pub struct AdvReport<'a> {
+    /// Type of the PDU
+    pub pdu_type: PduType,
+    /// Address of the sender (AdvA, or ScanA/InitA for `SCAN_REQ` and `CONNECT_IND`)
+    pub address: DeviceAddress,
+    /// The rest of the payload, e.g. the advertising data
+    pub data: &'a [u8],
+    /// Received signal strength in dBm
+    pub rssi: i8,
+    /// Channel the PDU has been received on
+    pub channel: AdvertisingChannel,
+    /// Time of reception in microseconds since the start of the scan window
+    pub timestamp: u32,
+}
+
+/// A passive BLE scanner.
+pub struct Scanner<'c> {
+    radio: Radio<'c>,
+    rx_pdu: [u8; PDU_SIZE],
+}
+
+impl<'c> Scanner<'c> {
+    /// Creates a passive scanner.
+    pub fn new(mut radio: Radio<'c>) -> Self {
+        configure(&mut radio);
+        Self {
+            radio,
+            rx_pdu: [0; PDU_SIZE],
+        }
+    }
+
+    /// Listens on `channel` for up to `window` microseconds, returning the first advertising
+    /// channel PDU that has been received with a valid CRC.
+    ///
+    /// Returns `Ok(None)` if no PDU has been received within the window.
+    pub fn scan<I>(
+        &mut self,
+        channel: AdvertisingChannel,
+        timer: &mut Timer<I>,
+        window: u32,
+    ) -> Result<Option<AdvReport<'_>>, Error>
+    where
+        I: timer::Instance,
+    {
+        set_channel(&mut self.radio, channel);
+
+        // Each `recv_timeout` restarts the timer, so keep track of the time already spent
+        let mut elapsed = 0;
+        while elapsed < window {
+            match self
+                .radio
+                .recv_timeout(&mut self.rx_pdu, timer, window - elapsed)
+            {
+                Ok(()) => {
+                    let timestamp = elapsed + timer.read();
+                    let rssi = self.radio.rssi();
+                    return parse_pdu(&self.rx_pdu, rssi, channel, timestamp).map(Some);
+                }
+                Err(radio::Error::Timeout) => break,
+                // Skip the corrupted PDU and continue listening
+                Err(radio::Error::Crc) => elapsed += timer.read(),
+                Err(e) => return Err(Error::Radio(e)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the radio.
+    pub fn free(self) -> Radio<'c> {
+        self.radio
+    }
+}
+
+fn parse_pdu(
+    pdu: &[u8; PDU_SIZE],
+    rssi: i8,
+    channel: AdvertisingChannel,
+    timestamp: u32,
+) -> Result<AdvReport<'_>, Error> {
+    let header = pdu[0];
+    let len = usize::from(pdu[1]);
+    if !(6..=MAX_PAYLOAD_LEN).contains(&len) {
+        return Err(Error::InvalidPdu);
+    }
+
+    let mut bytes = [0; 6];
+    bytes.copy_from_slice(&pdu[2..8]);
+
+    Ok(AdvReport {
+        pdu_type: PduType::from_bits(header),
+        address: DeviceAddress {
+            bytes,
+            // TxAdd is bit 6 of the header
+            random: header & 0x40 != 0,
+        },
+        data: &pdu[8..2 + len],
+        rssi,
+        channel,
+        timestamp,
+    })
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The advertising or scan response data is longer than 31 bytes
+    DataTooLong,
+    /// A PDU with an invalid length has been received
+    InvalidPdu,
+    /// The radio reported an error
+    Radio(radio::Error),
+}
+
+impl From<radio::Error> for Error {
+    fn from(e: radio::Error) -> Self {
+        Error::Radio(e)
+    }
+}
@@ -82,3 +82,19 @@ impl DelayUs<u8> for Delay {
         self.delay_us(u32(us))
     }
 }
+
+#[cfg(feature = "embedded-hal-1")]
+impl eh1::delay::DelayNs for Delay {
+    fn delay_ns(&mut self, ns: u32) {
+        // Round up, so that we never wait for less than requested.
+        DelayUs::delay_us(self, ns / 1_000 + u32::from(ns % 1_000 != 0))
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        DelayUs::delay_us(self, us)
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        DelayMs::delay_ms(self, ms)
+    }
+}
@@ -0,0 +1,268 @@
+//! Direction finding with the Constant Tone Extension (CTE), for Bluetooth angle of arrival
+//! (AoA) and angle of departure (AoD).
+//!
+//! With direction finding enabled, [`Radio::send`] appends a CTE to every packet, after the
+//! CRC. For AoD, the transmitter switches antennas during the CTE; for AoA, the receiver does.
+//! [`Radio::recv_with_iq`] receives a packet and stores the IQ samples taken during its CTE.
+//!
+//! Antennas are selected through up to 8 GPIOs (PSEL.DFEGPIO), driven from a switching pattern:
+//! the first entry is applied outside of the CTE, the second during the reference period and the
+//! following entries, in turn, in each switch slot.
+//!
+//! The nRF52840 RADIO does not implement direction finding, so this module is only available on
+//! the nRF52811 and nRF52833.
+
+use core::slice;
+
+use crate::{
+    gpio::{Output, Pin, PushPull},
+    radio::{Error, Radio},
+    slice_in_ram,
+    timer::{self, Timer},
+};
+
+/// Number of entries the switching pattern can hold.
+pub const MAX_PATTERN_LEN: usize = 40;
+
+/// Which side switches antennas during the CTE.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DfeMode {
+    /// Angle of arrival: the receiver switches antennas
+    Aoa,
+    /// Angle of departure: the transmitter switches antennas
+    Aod,
+}
+
+/// Time between two antenna switches.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwitchSpacing {
+    /// 4 µs
+    _4us = 1,
+    /// 2 µs
+    _2us = 2,
+    /// 1 µs
+    _1us = 3,
+}
+
+/// Time between two IQ samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SampleSpacing {
+    /// 4 µs
+    _4us = 1,
+    /// 2 µs
+    _2us = 2,
+    /// 1 µs
+    _1us = 3,
+    /// 500 ns
+    _500ns = 4,
+    /// 250 ns
+    _250ns = 5,
+    /// 125 ns
+    _125ns = 6,
+}
+
+/// Direction finding configuration (DFEMODE, DFECTRL1 and DFECTRL2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DfeConfig {
+    /// Angle of arrival or angle of departure
+    pub mode: DfeMode,
+    /// Length of the CTE in units of 8 µs, 2 to 20 for Bluetooth
+    pub cte_len: u8,
+    /// Time between antenna switches
+    pub switch_spacing: SwitchSpacing,
+    /// Time between IQ samples during the 8 µs reference period
+    pub reference_sample_spacing: SampleSpacing,
+    /// Time between IQ samples after the reference period
+    pub sample_spacing: SampleSpacing,
+    /// Number of times each switching pattern entry is repeated, 0 to 15
+    pub repeat_pattern: u8,
+    /// Delay of the first antenna switch, in 16 MHz cycles
+    pub switch_offset: i16,
+    /// Delay of the first IQ sample, in 16 MHz cycles
+    pub sample_offset: i16,
+}
+
+impl Default for DfeConfig {
+    /// AoA with a 160 µs CTE, 2 µs switch slots and one IQ sample per µs.
+    fn default() -> Self {
+        Self {
+            mode: DfeMode::Aoa,
+            cte_len: 20,
+            switch_spacing: SwitchSpacing::_2us,
+            reference_sample_spacing: SampleSpacing::_1us,
+            sample_spacing: SampleSpacing::_1us,
+            repeat_pattern: 0,
+            switch_offset: 0,
+            sample_offset: 0,
+        }
+    }
+}
+
+/// An IQ sample, as stored by the RADIO.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[repr(transparent)]
+pub struct IqSample(u32);
+
+impl IqSample {
+    /// Returns the in-phase component.
+    pub fn i(&self) -> i16 {
+        self.0 as u16 as i16
+    }
+
+    /// Returns the quadrature component.
+    pub fn q(&self) -> i16 {
+        (self.0 >> 16) as u16 as i16
+    }
+}
+
+impl<'c> Radio<'c> {
+    /// Enables direction finding, so that a CTE is sent after every packet and expected after
+    /// every received packet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.cte_len` is larger than 63 or `config.repeat_pattern` larger than 15.
+    pub fn set_direction_finding(&mut self, config: &DfeConfig) {
+        assert!(config.cte_len < 64 && config.repeat_pattern < 16);
+        let radio = self.regs();
+
+        // The CTE length is fixed, rather than read from the CTEInfo field of the packet.
+        radio.cteinlineconf.write(|w| unsafe { w.bits(0) });
+
+        // NOTE(unsafe) DFECTRL1 and DFECTRL2 are written as a whole; the CTE is placed after the
+        // CRC, and the samples are stored in the IQ format.
+        radio.dfectrl1.write(|w| unsafe {
+            w.bits(
+                u32::from(config.cte_len)
+                    | 1 << 7
+                    | (config.switch_spacing as u32) << 8
+                    | (config.reference_sample_spacing as u32) << 12
+                    | (config.sample_spacing as u32) << 16
+                    | u32::from(config.repeat_pattern) << 20,
+            )
+        });
+        radio.dfectrl2.write(|w| unsafe {
+            w.bits(
+                u32::from(config.switch_offset as u16 & 0x1FFF)
+                    | u32::from(config.sample_offset as u16 & 0xFFF) << 16,
+            )
+        });
+
+        let mode = match config.mode {
+            DfeMode::Aod => 2,
+            DfeMode::Aoa => 3,
+        };
+        radio.dfemode.write(|w| unsafe { w.bits(mode) });
+    }
+
+    /// Disables direction finding and disconnects the antenna switching GPIOs.
+    pub fn disable_direction_finding(&mut self) {
+        let radio = self.regs();
+        radio.dfemode.write(|w| unsafe { w.bits(0) });
+        for psel in radio.psel.dfegpio.iter() {
+            psel.write(|w| unsafe { w.bits(1 << 31) });
+        }
+    }
+
+    /// Connects the GPIOs driving the antenna switch; bit `n` of a switching pattern entry
+    /// drives `pins[n]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than 8 pins are passed.
+    pub fn set_antenna_pins(&mut self, pins: &[Pin<Output<PushPull>>]) {
+        let radio = self.regs();
+        assert!(pins.len() <= radio.psel.dfegpio.len());
+
+        for (i, psel) in radio.psel.dfegpio.iter().enumerate() {
+            let bits = match pins.get(i) {
+                Some(pin) => pin.psel_bits(),
+                None => 1 << 31,
+            };
+            psel.write(|w| unsafe { w.bits(bits) });
+        }
+    }
+
+    /// Replaces the antenna switching pattern.
+    ///
+    /// `pattern[0]` is applied outside of the CTE, `pattern[1]` during the reference period, and
+    /// the remaining entries in each switch slot, starting over when the end is reached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` holds less than 3 or more than [`MAX_PATTERN_LEN`] entries.
+    pub fn set_switch_pattern(&mut self, pattern: &[u8]) {
+        assert!((3..=MAX_PATTERN_LEN).contains(&pattern.len()));
+        let radio = self.regs();
+
+        radio.clearpattern.write(|w| unsafe { w.bits(1) });
+        for &entry in pattern {
+            radio
+                .switchpattern
+                .write(|w| unsafe { w.bits(u32::from(entry)) });
+        }
+    }
+
+    /// Receives a packet into `packet` and the IQ samples of its CTE into `samples`, blocking
+    /// until a packet has been received.
+    ///
+    /// Returns the number of samples stored. See `recv` for the layout of `packet`.
+    pub fn recv_with_iq(
+        &mut self,
+        packet: &mut [u8],
+        samples: &mut [IqSample],
+    ) -> Result<usize, Error> {
+        self.set_iq_buffer(samples)?;
+        let result = self.recv(packet);
+        let amount = self.release_iq_buffer();
+        result.map(|()| amount)
+    }
+
+    /// Listens for a packet for no longer than the specified amount of microseconds, receiving it
+    /// into `packet` and the IQ samples of its CTE into `samples`.
+    ///
+    /// Returns the number of samples stored. See `recv_timeout`.
+    pub fn recv_with_iq_timeout<I>(
+        &mut self,
+        packet: &mut [u8],
+        samples: &mut [IqSample],
+        timer: &mut Timer<I>,
+        microseconds: u32,
+    ) -> Result<usize, Error>
+    where
+        I: timer::Instance,
+    {
+        self.set_iq_buffer(samples)?;
+        let result = self.recv_timeout(packet, timer, microseconds);
+        let amount = self.release_iq_buffer();
+        result.map(|()| amount)
+    }
+
+    fn set_iq_buffer(&mut self, samples: &mut [IqSample]) -> Result<(), Error> {
+        // NOTE(unsafe) `IqSample` is a `u32`
+        let bytes =
+            unsafe { slice::from_raw_parts(samples.as_ptr() as *const u8, samples.len() * 4) };
+        if !slice_in_ram(bytes) {
+            return Err(Error::BufferNotInRAM);
+        }
+
+        let radio = self.regs();
+        radio
+            .dfepacket
+            .ptr
+            .write(|w| unsafe { w.bits(samples.as_mut_ptr() as u32) });
+        radio
+            .dfepacket
+            .maxcnt
+            .write(|w| unsafe { w.bits(samples.len().min(0xFFFF) as u32) });
+        Ok(())
+    }
+
+    /// Stops storing IQ samples, since the buffer is only borrowed for one reception, and
+    /// returns the number of samples stored.
+    fn release_iq_buffer(&mut self) -> usize {
+        let radio = self.regs();
+        radio.dfepacket.maxcnt.write(|w| unsafe { w.bits(0) });
+        radio.dfepacket.amount.read().bits() as usize
+    }
+}
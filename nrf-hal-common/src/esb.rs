@@ -0,0 +1,648 @@
+//! Enhanced ShockBurst (ESB), Nordic's proprietary 2.4 GHz protocol.
+//!
+//! This runs on top of [`radio::Radio`](crate::radio::Radio) and uses the packet format with
+//! dynamic payload length, which is compatible with the nRF24L01+: a 6-bit length field, a 2-bit
+//! packet ID (PID) and a NO_ACK flag, followed by up to 32 bytes of payload and a 1 or 2 byte
+//! CRC.
+//!
+//! A primary transmitter ([`Ptx`]) sends packets to one of up to 8 pipes and, unless the packet
+//! does not request an acknowledgment, waits for the ACK, retransmitting the packet if it does
+//! not arrive. A primary receiver ([`Prx`]) acknowledges received packets automatically,
+//! optionally with a payload, and drops retransmissions of packets it has already received.
+
+use core::sync::atomic::{compiler_fence, Ordering};
+
+use embedded_hal::timer::CountDown as _;
+
+use crate::{
+    pac::{timer0, RADIO},
+    ppi::ConfigurablePpi,
+    radio::{CrcConfig, Mode, PacketConfig, Preamble, Radio},
+    timer::{self, Timer},
+};
+
+/// Maximum length of a payload, in bytes.
+pub const MAX_PAYLOAD_LEN: usize = 32;
+
+/// Size of a packet in RAM: length, S1 (PID and NO_ACK) and payload.
+const PACKET_SIZE: usize = 2 + MAX_PAYLOAD_LEN;
+
+/// How long the transmitter waits for the address of an ACK after the end of a packet, in
+/// microseconds: the ramp-up of both radios, plus preamble and address at 1 Mbit/s.
+const ACK_TIMEOUT: u32 = 200;
+
+/// How long the transmitter waits for a started transfer to end, in microseconds: well above the
+/// ramp-up plus the longest packet at 1 Mbit/s.
+const TRANSFER_TIMEOUT: u32 = 1_000;
+
+/// Data rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bitrate {
+    /// 1 Mbit/s
+    _1Mbit,
+    /// 2 Mbit/s
+    _2Mbit,
+}
+
+/// Length of the CRC.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Crc {
+    /// 8-bit CRC
+    _8bit,
+    /// 16-bit CRC
+    _16bit,
+}
+
+/// Addresses of the pipes.
+///
+/// The address of pipe `n` is made up of a base address (`base0` for pipe 0, `base1` for pipes 1
+/// to 7) and the prefix byte `prefixes[n]`. The byte order is the one of Nordic's `nrf_esb`
+/// library.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Addresses {
+    /// Base address of pipe 0
+    pub base0: [u8; 4],
+    /// Base address of pipes 1 to 7
+    pub base1: [u8; 4],
+    /// Prefix byte of each pipe
+    pub prefixes: [u8; 8],
+    /// Length of the addresses in bytes, prefix included: 3 to 5
+    pub len: u8,
+}
+
+impl Default for Addresses {
+    /// The nRF24L01+ default addresses.
+    fn default() -> Self {
+        Self {
+            base0: [0xE7; 4],
+            base1: [0xC2; 4],
+            prefixes: [0xE7, 0xC2, 0xC3, 0xC4, 0xC5, 0xC6, 0xC7, 0xC8],
+            len: 5,
+        }
+    }
+}
+
+/// ESB configuration, shared by the transmitter and the receiver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Data rate
+    pub bitrate: Bitrate,
+    /// CRC length
+    pub crc: Crc,
+    /// Pipe addresses
+    pub addresses: Addresses,
+    /// RF channel: the frequency is 2400 MHz plus `channel`, 0 to 100
+    pub channel: u8,
+    /// Maximum length of the payloads, at most `MAX_PAYLOAD_LEN`; longer packets are truncated
+    pub max_payload_len: u8,
+    /// Time between the end of a packet and its retransmission, in microseconds; at least 250
+    pub retransmit_delay: u32,
+    /// Number of retransmissions of a packet that has not been acknowledged, before giving up
+    pub retransmit_count: u8,
+    /// Pipes the receiver listens on, with bit `n` enabling pipe `n`
+    pub rx_pipes: u8,
+}
+
+impl Default for Config {
+    /// 2 Mbit/s, 16-bit CRC, channel 2, 3 retransmissions after 250 µs, and pipes 0 and 1
+    /// enabled, like the nRF24L01+ after reset.
+    fn default() -> Self {
+        Self {
+            bitrate: Bitrate::_2Mbit,
+            crc: Crc::_16bit,
+            addresses: Addresses::default(),
+            channel: 2,
+            max_payload_len: MAX_PAYLOAD_LEN as u8,
+            retransmit_delay: 250,
+            retransmit_count: 3,
+            rx_pipes: 0b11,
+        }
+    }
+}
+
+/// Configures `radio` for ESB.
+///
+/// # Panics
+///
+/// Panics if a setting of `config` is out of range.
+fn configure(radio: &mut Radio<'_>, config: &Config) {
+    assert!(usize::from(config.max_payload_len) <= MAX_PAYLOAD_LEN);
+    assert!((3..=5).contains(&config.addresses.len));
+    assert!(config.channel <= 100);
+    assert!(config.retransmit_delay >= 250);
+
+    radio.set_mode(match config.bitrate {
+        Bitrate::_1Mbit => Mode::Nrf1Mbit,
+        Bitrate::_2Mbit => Mode::Nrf2Mbit,
+    });
+    radio.set_packet_config(&PacketConfig {
+        length_bits: 6,
+        s0_byte: false,
+        // 2-bit PID and NO_ACK flag
+        s1_bits: 3,
+        s1_in_ram: false,
+        preamble: Preamble::_8bit,
+        max_len: config.max_payload_len,
+        static_len: 0,
+        base_address_len: config.addresses.len - 1,
+        big_endian: true,
+        whitening: false,
+    });
+    radio.set_crc(match config.crc {
+        Crc::_8bit => CrcConfig {
+            len: 1,
+            skip_address: false,
+            polynomial: 0x107,
+            init: 0xFF,
+        },
+        Crc::_16bit => CrcConfig {
+            len: 2,
+            skip_address: false,
+            polynomial: 0x11021,
+            init: 0xFFFF,
+        },
+    });
+    radio.set_frequency(2400 + u16::from(config.channel));
+
+    // The radio sends the address least significant bit first, the nRF24L01+ most significant
+    // bit first.
+    let addresses = &config.addresses;
+    let base = |bytes: [u8; 4]| u32::from_be_bytes(bytes.map(u8::reverse_bits));
+    radio.set_base_address(0, base(addresses.base0));
+    radio.set_base_address(1, base(addresses.base1));
+    for (pipe, prefix) in addresses.prefixes.iter().enumerate() {
+        radio.set_prefix(pipe as u8, prefix.reverse_bits());
+    }
+}
+
+/// Returns the S1 field of a packet: the PID and the NO_ACK flag.
+fn s1(pid: u8, no_ack: bool) -> u8 {
+    (pid & 0b11) << 1 | u8::from(no_ack)
+}
+
+/// Returns the PID of a received packet.
+fn packet_pid(packet: &[u8; PACKET_SIZE]) -> u8 {
+    (packet[1] >> 1) & 0b11
+}
+
+/// Returns the payload of a received packet.
+fn packet_payload(packet: &[u8; PACKET_SIZE]) -> &[u8] {
+    let len = usize::from(packet[0]).min(MAX_PAYLOAD_LEN);
+    &packet[2..2 + len]
+}
+
+/// Disables the radio, cancelling a reception or a pending turnaround.
+fn stop(radio: &Radio<'_>) {
+    let radio = radio.regs();
+    radio.shorts.reset();
+    radio.events_disabled.reset();
+    radio.tasks_disable.write(|w| unsafe { w.bits(1) });
+    while radio.events_disabled.read().bits() == 0 {}
+    radio.events_disabled.reset();
+    compiler_fence(Ordering::Acquire);
+}
+
+/// Returns the current value of a running `timer`, using CC[2].
+fn capture(timer: &timer0::RegisterBlock) -> u32 {
+    timer.tasks_capture[2].write(|w| unsafe { w.bits(1) });
+    timer.cc[2].read().bits()
+}
+
+/// Waits for the DISABLED event of the radio, giving up once `timer` reaches `deadline`.
+///
+/// Returns `false` on timeout, leaving the radio as is.
+fn wait_disabled(radio: &RADIO, timer: &timer0::RegisterBlock, deadline: u32) -> bool {
+    timer.cc[2].write(|w| unsafe { w.bits(deadline) });
+    timer.events_compare[2].reset();
+    while radio.events_disabled.read().bits() == 0 {
+        if timer.events_compare[2].read().bits() != 0 {
+            return false;
+        }
+    }
+    radio.events_disabled.reset();
+    true
+}
+
+/// An ESB primary transmitter.
+///
+/// The `timer` measures the ACK timeout and the retransmit delay; the `ppi` channel starts
+/// retransmissions from the timer, so they are sent exactly `retransmit_delay` after the end of
+/// the previous attempt.
+pub struct Ptx<'c, T, P> {
+    radio: Radio<'c>,
+    timer: T,
+    ppi: P,
+    max_payload_len: u8,
+    retransmit_delay: u32,
+    retransmit_count: u8,
+    pids: [u8; 8],
+    tx: [u8; PACKET_SIZE],
+    rx: [u8; PACKET_SIZE],
+}
+
+impl<'c, T, P> Ptx<'c, T, P>
+where
+    T: timer::Instance,
+    P: ConfigurablePpi,
+{
+    /// Creates a primary transmitter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a setting of `config` is out of range.
+    pub fn new(mut radio: Radio<'c>, timer: T, mut ppi: P, config: &Config) -> Self {
+        configure(&mut radio, config);
+
+        let t = timer.as_timer0();
+        t.tasks_stop.write(|w| unsafe { w.bits(1) });
+        t.shorts.reset();
+        t.prescaler.write(|w| unsafe { w.prescaler().bits(4) }); // 1 MHz
+        t.bitmode.write(|w| w.bitmode()._32bit());
+
+        ppi.disable();
+        ppi.set_event_endpoint(&t.events_compare[0]);
+        ppi.set_task_endpoint(&radio.regs().tasks_txen);
+
+        Self {
+            radio,
+            timer,
+            ppi,
+            max_payload_len: config.max_payload_len,
+            retransmit_delay: config.retransmit_delay,
+            retransmit_count: config.retransmit_count,
+            pids: [0; 8],
+            tx: [0; PACKET_SIZE],
+            rx: [0; PACKET_SIZE],
+        }
+    }
+
+    /// Sends `payload` to `pipe` (0 to 7), blocking until it has been acknowledged.
+    ///
+    /// If `ack` is `false`, the packet is sent once with the NO_ACK flag set and no ACK is
+    /// awaited. Otherwise, the packet is retransmitted up to `retransmit_count` times until it
+    /// is acknowledged, and the payload of the ACK (which may be empty) is returned. If it is
+    /// never acknowledged, the `MaxRetransmits` error is returned.
+    ///
+    /// Returns the `Timeout` error if the radio doesn't finish a transfer in time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pipe` is larger than 7.
+    pub fn send(&mut self, pipe: u8, payload: &[u8], ack: bool) -> Result<&[u8], Error> {
+        assert!(pipe < 8);
+        if payload.len() > usize::from(self.max_payload_len) {
+            return Err(Error::PayloadTooLong);
+        }
+
+        // A new PID lets the receiver tell new packets from retransmissions.
+        let pid = (self.pids[usize::from(pipe)] + 1) & 0b11;
+        self.pids[usize::from(pipe)] = pid;
+        self.tx[0] = payload.len() as u8;
+        self.tx[1] = s1(pid, !ack);
+        self.tx[2..2 + payload.len()].copy_from_slice(payload);
+
+        self.radio.set_tx_address(pipe);
+        self.radio.set_rx_addresses(1 << pipe);
+
+        let tx_ptr = self.tx.as_ptr() as u32;
+        let rx_ptr = self.rx.as_mut_ptr() as u32;
+        let radio = self.radio.regs();
+        let timer = self.timer.as_timer0();
+
+        for attempt in 0..=self.retransmit_count {
+            radio.events_disabled.reset();
+            radio.packetptr.write(|w| unsafe { w.bits(tx_ptr) });
+            // Turn around to receive the ACK right after the packet.
+            radio.shorts.write(|w| {
+                w.ready_start()
+                    .enabled()
+                    .end_disable()
+                    .enabled()
+                    .disabled_rxen()
+                    .bit(ack)
+            });
+            compiler_fence(Ordering::Release);
+
+            // Pause the timer while the PPI channel is off, so the retransmit delay can't end
+            // unnoticed between checking it and enabling the channel.
+            timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+            let start = if attempt == 0 {
+                timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+                radio.tasks_txen.write(|w| unsafe { w.bits(1) });
+                0
+            } else {
+                let now = capture(timer);
+                if now >= self.retransmit_delay {
+                    // the retransmit delay is already over
+                    radio.tasks_txen.write(|w| unsafe { w.bits(1) });
+                    now
+                } else {
+                    // the timer starts the retransmission
+                    self.ppi.enable();
+                    self.retransmit_delay
+                }
+            };
+            timer.tasks_start.write(|w| unsafe { w.bits(1) });
+
+            // Wait for the end of the transmission.
+            let sent = wait_disabled(radio, timer, start.wrapping_add(TRANSFER_TIMEOUT));
+            self.ppi.disable();
+            if !sent {
+                return Err(self.abort());
+            }
+
+            if !ack {
+                radio.shorts.reset();
+                timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+                return Ok(&[]);
+            }
+
+            // The receiver is ramping up. Time the ACK timeout (CC[1]) and the retransmit delay
+            // (CC[0]) from the end of the packet.
+            timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+            timer.tasks_clear.write(|w| unsafe { w.bits(1) });
+            timer.events_compare[0].reset();
+            timer.events_compare[1].reset();
+            timer.cc[0].write(|w| unsafe { w.bits(self.retransmit_delay) });
+            timer.cc[1].write(|w| unsafe { w.bits(ACK_TIMEOUT) });
+            timer.tasks_start.write(|w| unsafe { w.bits(1) });
+
+            radio.events_address.reset();
+            radio.packetptr.write(|w| unsafe { w.bits(rx_ptr) });
+            // Receive the ACK and sample its RSSI.
+            radio.shorts.write(|w| {
+                w.ready_start()
+                    .enabled()
+                    .end_disable()
+                    .enabled()
+                    .address_rssistart()
+                    .enabled()
+            });
+
+            let mut received = false;
+            while timer.events_compare[1].read().bits() == 0 {
+                if radio.events_address.read().bits() != 0 {
+                    received = true;
+                    break;
+                }
+            }
+
+            if !received {
+                stop(&self.radio);
+                continue;
+            }
+
+            let deadline = capture(timer).wrapping_add(TRANSFER_TIMEOUT);
+            if !wait_disabled(radio, timer, deadline) {
+                return Err(self.abort());
+            }
+            radio.shorts.reset();
+            radio.tasks_rssistop.write(|w| unsafe { w.bits(1) });
+            compiler_fence(Ordering::Acquire);
+
+            // The ACK carries the PID of the packet it acknowledges.
+            if radio.crcstatus.read().crcstatus().bit_is_set() && packet_pid(&self.rx) == pid {
+                timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+                return Ok(packet_payload(&self.rx));
+            }
+        }
+
+        timer.tasks_stop.write(|w| unsafe { w.bits(1) });
+        Err(Error::MaxRetransmits)
+    }
+
+    /// Stops the radio and the timer after a transfer didn't end in time.
+    fn abort(&mut self) -> Error {
+        stop(&self.radio);
+        self.timer
+            .as_timer0()
+            .tasks_stop
+            .write(|w| unsafe { w.bits(1) });
+        Error::Timeout
+    }
+
+    /// Returns the RSSI of the last ACK received by `send`, in dBm.
+    pub fn rssi(&self) -> i8 {
+        self.radio.rssi()
+    }
+
+    /// Returns the radio, the timer and the PPI channel.
+    pub fn free(mut self) -> (Radio<'c>, T, P) {
+        self.ppi.disable();
+        self.timer
+            .as_timer0()
+            .tasks_stop
+            .write(|w| unsafe { w.bits(1) });
+        (self.radio, self.timer, self.ppi)
+    }
+}
+
+/// State of the ACK payload of a pipe.
+#[derive(Clone, Copy, PartialEq)]
+enum AckState {
+    /// Empty ACKs are sent
+    Empty,
+    /// The payload is sent with the next ACK
+    Pending,
+    /// The payload has been sent, and is sent again if the packet is retransmitted
+    Sent,
+}
+
+/// A packet received by a [`Prx`].
+#[derive(Debug)]
+pub struct Received<'a> {
+    /// Pipe the packet has been received on
+    pub pipe: u8,
+    /// Payload of the packet
+    pub payload: &'a [u8],
+    /// Received signal strength in dBm
+    pub rssi: i8,
+}
+
+/// An ESB primary receiver.
+pub struct Prx<'c> {
+    radio: Radio<'c>,
+    max_payload_len: u8,
+    rx: [u8; PACKET_SIZE],
+    // PID and CRC of the last packet received on each pipe, to drop retransmissions
+    last: [Option<(u8, u32)>; 8],
+    acks: [[u8; PACKET_SIZE]; 8],
+    ack_states: [AckState; 8],
+    empty_ack: [u8; 2],
+}
+
+impl<'c> Prx<'c> {
+    /// Creates a primary receiver, listening on `config.rx_pipes`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a setting of `config` is out of range.
+    pub fn new(mut radio: Radio<'c>, config: &Config) -> Self {
+        configure(&mut radio, config);
+        radio.set_rx_addresses(config.rx_pipes);
+
+        Self {
+            radio,
+            max_payload_len: config.max_payload_len,
+            rx: [0; PACKET_SIZE],
+            last: [None; 8],
+            acks: [[0; PACKET_SIZE]; 8],
+            ack_states: [AckState::Empty; 8],
+            empty_ack: [0; 2],
+        }
+    }
+
+    /// Sets the payload sent with the next ACK on `pipe` (0 to 7).
+    ///
+    /// The payload replaces the previous one, even if that has been sent and the packet it
+    /// acknowledged is retransmitted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pipe` is larger than 7.
+    pub fn set_ack_payload(&mut self, pipe: u8, payload: &[u8]) -> Result<(), Error> {
+        assert!(pipe < 8);
+        if payload.len() > usize::from(self.max_payload_len) {
+            return Err(Error::PayloadTooLong);
+        }
+
+        let ack = &mut self.acks[usize::from(pipe)];
+        ack[0] = payload.len() as u8;
+        ack[2..2 + payload.len()].copy_from_slice(payload);
+        self.ack_states[usize::from(pipe)] = AckState::Pending;
+        Ok(())
+    }
+
+    /// Receives a packet, blocking until a new packet has been received.
+    ///
+    /// Packets are acknowledged unless they have the NO_ACK flag set. Retransmissions of the
+    /// last packet received on a pipe are acknowledged again, but not returned.
+    pub fn recv(&mut self) -> Result<Received<'_>, Error> {
+        let pipe = self.recv_with(|| false)?;
+        Ok(self.received(pipe))
+    }
+
+    /// Receives a packet like `recv`, giving up after `microseconds`.
+    ///
+    /// If no packet has been received within the specified time then the `Timeout` error is
+    /// returned. Note that the radio ramp-up time is included in the timeout count.
+    pub fn recv_timeout<I>(
+        &mut self,
+        timer: &mut Timer<I>,
+        microseconds: u32,
+    ) -> Result<Received<'_>, Error>
+    where
+        I: timer::Instance,
+    {
+        timer.start(microseconds);
+        let pipe = self.recv_with(|| timer.wait().is_ok())?;
+        Ok(self.received(pipe))
+    }
+
+    /// Returns the radio.
+    pub fn free(self) -> Radio<'c> {
+        self.radio
+    }
+
+    fn received(&self, pipe: u8) -> Received<'_> {
+        Received {
+            pipe,
+            payload: packet_payload(&self.rx),
+            rssi: self.radio.rssi(),
+        }
+    }
+
+    /// Receives and acknowledges packets until a new packet has been received, returning its
+    /// pipe, or until `timed_out` returns `true` while waiting for a packet.
+    fn recv_with(&mut self, mut timed_out: impl FnMut() -> bool) -> Result<u8, Error> {
+        let rx_ptr = self.rx.as_mut_ptr() as u32;
+        loop {
+            let radio = self.radio.regs();
+            radio.events_address.reset();
+            radio.events_disabled.reset();
+            radio.packetptr.write(|w| unsafe { w.bits(rx_ptr) });
+            // Turn around to send the ACK right after the packet, and sample the RSSI.
+            radio.shorts.write(|w| {
+                w.ready_start()
+                    .enabled()
+                    .end_disable()
+                    .enabled()
+                    .disabled_txen()
+                    .enabled()
+                    .address_rssistart()
+                    .enabled()
+            });
+            compiler_fence(Ordering::Release);
+            radio.tasks_rxen.write(|w| unsafe { w.bits(1) });
+
+            while radio.events_address.read().bits() == 0 {
+                if timed_out() {
+                    stop(&self.radio);
+                    return Err(Error::Timeout);
+                }
+            }
+
+            // Wait for the end of the packet; the radio then turns around to TX.
+            while radio.events_disabled.read().bits() == 0 {}
+            radio.events_disabled.reset();
+            radio.tasks_rssistop.write(|w| unsafe { w.bits(1) });
+            compiler_fence(Ordering::Acquire);
+
+            if radio.crcstatus.read().crcstatus().bit_is_clear() {
+                stop(&self.radio);
+                continue;
+            }
+
+            let pipe = self.radio.rx_address();
+            let index = usize::from(pipe);
+            let pid = packet_pid(&self.rx);
+            let crc = radio.rxcrc.read().bits();
+            let duplicate = self.last[index] == Some((pid, crc));
+            self.last[index] = Some((pid, crc));
+
+            if self.rx[1] & 1 != 0 {
+                // NO_ACK
+                stop(&self.radio);
+            } else {
+                // A new packet confirms that the ACK payload has been delivered.
+                if !duplicate && self.ack_states[index] == AckState::Sent {
+                    self.ack_states[index] = AckState::Empty;
+                }
+                let ack: &mut [u8] = if self.ack_states[index] == AckState::Empty {
+                    &mut self.empty_ack
+                } else {
+                    self.ack_states[index] = AckState::Sent;
+                    &mut self.acks[index]
+                };
+                ack[1] = s1(pid, false);
+
+                // The transmitter is ramping up and starts sending the ACK once it is ready.
+                let ack_ptr = ack.as_ptr() as u32;
+                radio.packetptr.write(|w| unsafe { w.bits(ack_ptr) });
+                radio
+                    .shorts
+                    .write(|w| w.ready_start().enabled().end_disable().enabled());
+                compiler_fence(Ordering::Release);
+
+                while radio.events_disabled.read().bits() == 0 {}
+                radio.events_disabled.reset();
+                radio.shorts.reset();
+            }
+
+            if !duplicate {
+                return Ok(pipe);
+            }
+        }
+    }
+}
+
+/// ESB error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// The payload is longer than `max_payload_len`
+    PayloadTooLong,
+    /// The packet has not been acknowledged, even after retransmissions
+    MaxRetransmits,
+    /// No packet has been received in time, or the radio didn't finish a transfer in time
+    Timeout,
+}
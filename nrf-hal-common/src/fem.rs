@@ -0,0 +1,290 @@
+//! Control of RF front-end modules (FEM), such as the nRF21540 or the SKY66112.
+//!
+//! A front-end module adds a power amplifier (PA) and a low-noise amplifier (LNA) to the radio,
+//! each switched on through an enable pin. The amplifiers need some time to settle, so they have
+//! to be enabled slightly before the RADIO starts transmitting or receiving.
+//!
+//! [`Fem`] drives the PA and LNA enable pins from hardware, through GPIOTE tasks and PPI
+//! channels: a TIMER started together with the RADIO ramp-up switches the amplifier on the
+//! configured lead time before the RADIO is ready, and the RADIO DISABLED event switches both
+//! amplifiers off. For a transmission started by Clear Channel Assessment, the CCAIDLE event
+//! switches the PA on and the LNA off. Radio drivers use it through the [`FrontEnd`] trait.
+
+use crate::{
+    gpio::{Output, Pin, PushPull},
+    gpiote::GpioteChannel,
+    pac::RADIO,
+    ppi::ConfigurablePpi,
+    timer,
+};
+
+/// Direction of a RADIO ramp-up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    /// Transmission, using the PA
+    Tx,
+    /// Reception, using the LNA
+    Rx,
+}
+
+/// What the RADIO ramp-up an amplifier is prepared for starts with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    /// The radio driver enables the RADIO right away
+    Now,
+    /// A shortcut enables the RADIO on the next DISABLED event, for a turnaround
+    Disabled,
+    /// The CCAIDLE_TXEN shortcut enables the transmitter once Clear Channel Assessment finds
+    /// the channel idle
+    #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+    CcaIdle,
+}
+
+/// Interface between radio drivers and a front-end module.
+pub trait FrontEnd {
+    /// Prepares the amplifier of `direction` to be switched on, `start_us` microseconds before
+    /// the RADIO starts transmitting or receiving, counted from the `trigger`.
+    ///
+    /// Called by the radio driver right before it enables the RADIO or arms the shortcut that
+    /// does. With `Trigger::Now`, both amplifiers are switched off first; otherwise, the
+    /// amplifier in use stays on until the trigger, e.g. the LNA during Clear Channel
+    /// Assessment.
+    fn enable(&mut self, radio: &RADIO, direction: Direction, start_us: u32, trigger: Trigger);
+
+    /// Cancels a prepared switch-on and switches both amplifiers off.
+    fn disable(&mut self);
+
+    /// Returns the gain of the PA, in dB.
+    fn pa_gain(&self) -> i8;
+}
+
+impl<F: FrontEnd + ?Sized> FrontEnd for &mut F {
+    fn enable(&mut self, radio: &RADIO, direction: Direction, start_us: u32, trigger: Trigger) {
+        (**self).enable(radio, direction, start_us, trigger)
+    }
+
+    fn disable(&mut self) {
+        (**self).disable()
+    }
+
+    fn pa_gain(&self) -> i8 {
+        (**self).pa_gain()
+    }
+}
+
+/// No front-end module: the RADIO drives the antenna directly.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NoFem;
+
+impl FrontEnd for NoFem {
+    fn enable(&mut self, _: &RADIO, _: Direction, _: u32, _: Trigger) {}
+
+    fn disable(&mut self) {}
+
+    fn pa_gain(&self) -> i8 {
+        0
+    }
+}
+
+/// The PA and LNA enable pins of a front-end module, both active high.
+pub struct Pins {
+    /// PA enable pin
+    pub pa: Pin<Output<PushPull>>,
+    /// LNA enable pin
+    pub lna: Pin<Output<PushPull>>,
+}
+
+/// Timing and gain of a front-end module.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FemConfig {
+    /// Time the PA needs between its enable pin going high and the start of a transmission, in
+    /// microseconds
+    pub pa_lead_us: u32,
+    /// Time the LNA needs between its enable pin going high and the start of a reception, in
+    /// microseconds
+    pub lna_lead_us: u32,
+    /// Gain of the PA in dB, compensated for by radio drivers when setting the TX power
+    pub pa_gain: i8,
+}
+
+impl FemConfig {
+    /// The nRF21540 with its default +20 dBm gain.
+    pub const fn nrf21540() -> Self {
+        Self {
+            pa_lead_us: 13,
+            lna_lead_us: 13,
+            pa_gain: 20,
+        }
+    }
+
+    /// The SKY66112-11.
+    pub const fn sky66112() -> Self {
+        Self {
+            pa_lead_us: 23,
+            lna_lead_us: 5,
+            pa_gain: 21,
+        }
+    }
+}
+
+/// A front-end module whose amplifiers are switched by GPIOTE tasks.
+///
+/// Uses two GPIOTE channels, a TIMER and three PPI channels:
+///
+/// - `ppi_on`: TIMER COMPARE\[0\] to the GPIOTE SET task of the amplifier being used.
+/// - `ppi_off`: RADIO DISABLED to the GPIOTE CLR tasks of both amplifiers.
+/// - `ppi_trigger`: RADIO DISABLED to TIMER START, for turnarounds, or RADIO CCAIDLE to the
+///   GPIOTE SET task of the PA and CLR task of the LNA, for transmissions after CCA.
+pub struct Fem<'g, T, P1, P2, P3> {
+    timer: T,
+    pa: GpioteChannel<'g>,
+    lna: GpioteChannel<'g>,
+    pins: Pins,
+    ppi_on: P1,
+    ppi_off: P2,
+    ppi_trigger: P3,
+    config: FemConfig,
+}
+
+impl<'g, T, P1, P2, P3> Fem<'g, T, P1, P2, P3>
+where
+    T: timer::Instance,
+    P1: ConfigurablePpi,
+    P2: ConfigurablePpi,
+    P3: ConfigurablePpi,
+{
+    /// Takes control of the `pins` through the `pa` and `lna` GPIOTE channels, switching both
+    /// amplifiers off.
+    ///
+    /// The `timer` is configured as a one-shot 1 MHz timer.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        timer: T,
+        pa: GpioteChannel<'g>,
+        lna: GpioteChannel<'g>,
+        pins: Pins,
+        ppi_on: P1,
+        mut ppi_off: P2,
+        ppi_trigger: P3,
+        config: FemConfig,
+    ) -> Self {
+        pa.output_pin(&pins.pa).init_low();
+        lna.output_pin(&pins.lna).init_low();
+
+        let t = timer.as_timer0();
+        t.tasks_stop.write(|w| unsafe { w.bits(1) });
+        t.shorts
+            .write(|w| w.compare0_clear().enabled().compare0_stop().enabled());
+        t.prescaler.write(|w| unsafe { w.prescaler().bits(4) }); // 1 MHz
+        t.bitmode.write(|w| w.bitmode()._32bit());
+        t.tasks_clear.write(|w| unsafe { w.bits(1) });
+
+        ppi_off.set_task_endpoint(pa.task_clr());
+        ppi_off.set_fork_task_endpoint(lna.task_clr());
+
+        Self {
+            timer,
+            pa,
+            lna,
+            pins,
+            ppi_on,
+            ppi_off,
+            ppi_trigger,
+            config,
+        }
+    }
+
+    /// Switches both amplifiers off and returns the resources.
+    ///
+    /// The pins remain configured as GPIOTE task outputs until the channels are reconfigured.
+    #[allow(clippy::type_complexity)]
+    pub fn free(mut self) -> (T, GpioteChannel<'g>, GpioteChannel<'g>, Pins, P1, P2, P3) {
+        FrontEnd::disable(&mut self);
+        self.ppi_on.disable();
+        self.ppi_off.disable();
+        (
+            self.timer,
+            self.pa,
+            self.lna,
+            self.pins,
+            self.ppi_on,
+            self.ppi_off,
+            self.ppi_trigger,
+        )
+    }
+}
+
+impl<'g, T, P1, P2, P3> FrontEnd for Fem<'g, T, P1, P2, P3>
+where
+    T: timer::Instance,
+    P1: ConfigurablePpi,
+    P2: ConfigurablePpi,
+    P3: ConfigurablePpi,
+{
+    fn enable(&mut self, radio: &RADIO, direction: Direction, start_us: u32, trigger: Trigger) {
+        self.ppi_trigger.disable();
+        self.ppi_off.set_event_endpoint(&radio.events_disabled);
+        self.ppi_off.enable();
+
+        match trigger {
+            Trigger::Now => self.disable(),
+            Trigger::Disabled => {
+                let t = self.timer.as_timer0();
+                t.tasks_stop.write(|w| unsafe { w.bits(1) });
+                t.tasks_clear.write(|w| unsafe { w.bits(1) });
+            }
+            #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+            Trigger::CcaIdle => {
+                // The transmitter ramp-up that follows takes longer than the lead time of the PA,
+                // so CCAIDLE switches the amplifiers directly, leaving the TIMER to the LNA
+                // switch-on that may still be pending for the CCA itself.
+                let (on, off) = match direction {
+                    Direction::Tx => (&self.pa, &self.lna),
+                    Direction::Rx => (&self.lna, &self.pa),
+                };
+                self.ppi_trigger.set_event_endpoint(&radio.events_ccaidle);
+                self.ppi_trigger.set_task_endpoint(on.task_set());
+                self.ppi_trigger.set_fork_task_endpoint(off.task_clr());
+                self.ppi_trigger.enable();
+                return;
+            }
+        }
+
+        let (amplifier, lead_us) = match direction {
+            Direction::Tx => (&self.pa, self.config.pa_lead_us),
+            Direction::Rx => (&self.lna, self.config.lna_lead_us),
+        };
+        self.ppi_on
+            .set_event_endpoint(&self.timer.as_timer0().events_compare[0]);
+        self.ppi_on.set_task_endpoint(amplifier.task_set());
+        self.ppi_on.enable();
+
+        // a compare value of 0 would only match once the timer wraps around
+        let delay = start_us.saturating_sub(lead_us).max(1);
+        let t = self.timer.as_timer0();
+        t.cc[0].write(|w| unsafe { w.bits(delay) });
+        t.events_compare[0].reset();
+
+        if trigger == Trigger::Now {
+            t.tasks_start.write(|w| unsafe { w.bits(1) });
+        } else {
+            self.ppi_trigger.set_event_endpoint(&radio.events_disabled);
+            self.ppi_trigger.set_task_endpoint(&t.tasks_start);
+            self.ppi_trigger.clear_fork_task_endpoint();
+            self.ppi_trigger.enable();
+        }
+    }
+
+    fn disable(&mut self) {
+        self.ppi_trigger.disable();
+        let t = self.timer.as_timer0();
+        t.tasks_stop.write(|w| unsafe { w.bits(1) });
+        t.tasks_clear.write(|w| unsafe { w.bits(1) });
+        self.pa.clear();
+        self.lna.clear();
+    }
+
+    fn pa_gain(&self) -> i8 {
+        self.config.pa_gain
+    }
+}
@@ -309,6 +309,53 @@ impl<MODE> StatefulOutputPin for Pin<Output<MODE>> {
     }
 }
 
+#[cfg(feature = "embedded-hal-1")]
+impl<MODE> eh1::digital::ErrorType for Pin<MODE> {
+    type Error = core::convert::Infallible;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<MODE> eh1::digital::InputPin for Pin<Input<MODE>> {
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        eh1::digital::InputPin::is_low(self).map(|v| !v)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.block().in_.read().bits() & (1 << self.pin()) == 0)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<MODE> eh1::digital::OutputPin for Pin<Output<MODE>> {
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe {
+            self.block().outset.write(|w| w.bits(1u32 << self.pin()));
+        }
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe {
+            self.block().outclr.write(|w| w.bits(1u32 << self.pin()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<MODE> eh1::digital::StatefulOutputPin for Pin<Output<MODE>> {
+    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+        eh1::digital::StatefulOutputPin::is_set_low(self).map(|v| !v)
+    }
+
+    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+        // NOTE(unsafe) atomic read with no side effects
+        Ok(self.block().out.read().bits() & (1 << self.pin()) == 0)
+    }
+}
+
 /// Pin configuration for open-drain mode.
 pub enum OpenDrainConfig {
     Disconnect0Standard1,
@@ -574,6 +621,49 @@ macro_rules! gpio {
                         Ok(unsafe { ((*$PX::ptr()).out.read().bits() & (1 << $i)) == 0 })
                     }
                 }
+
+                #[cfg(feature = "embedded-hal-1")]
+                impl<MODE> eh1::digital::ErrorType for $PXi<MODE> {
+                    type Error = core::convert::Infallible;
+                }
+
+                #[cfg(feature = "embedded-hal-1")]
+                impl<MODE> eh1::digital::InputPin for $PXi<Input<MODE>> {
+                    fn is_high(&mut self) -> Result<bool, Self::Error> {
+                        eh1::digital::InputPin::is_low(self).map(|v| !v)
+                    }
+
+                    fn is_low(&mut self) -> Result<bool, Self::Error> {
+                        Ok(unsafe { ((*$PX::ptr()).in_.read().bits() & (1 << $i)) == 0 })
+                    }
+                }
+
+                #[cfg(feature = "embedded-hal-1")]
+                impl<MODE> eh1::digital::OutputPin for $PXi<Output<MODE>> {
+                    fn set_high(&mut self) -> Result<(), Self::Error> {
+                        // NOTE(unsafe) atomic write to a stateless register
+                        unsafe { (*$PX::ptr()).outset.write(|w| w.bits(1u32 << $i)); }
+                        Ok(())
+                    }
+
+                    fn set_low(&mut self) -> Result<(), Self::Error> {
+                        // NOTE(unsafe) atomic write to a stateless register
+                        unsafe { (*$PX::ptr()).outclr.write(|w| w.bits(1u32 << $i)); }
+                        Ok(())
+                    }
+                }
+
+                #[cfg(feature = "embedded-hal-1")]
+                impl<MODE> eh1::digital::StatefulOutputPin for $PXi<Output<MODE>> {
+                    fn is_set_high(&mut self) -> Result<bool, Self::Error> {
+                        eh1::digital::StatefulOutputPin::is_set_low(self).map(|v| !v)
+                    }
+
+                    fn is_set_low(&mut self) -> Result<bool, Self::Error> {
+                        // NOTE(unsafe) atomic read with no side effects
+                        Ok(unsafe { ((*$PX::ptr()).out.read().bits() & (1 << $i)) == 0 })
+                    }
+                }
             )+
         }
     }
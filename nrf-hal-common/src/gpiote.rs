@@ -448,3 +448,12 @@ impl GpioteOutputPin for Pin<Output<PushPull>> {
         self.port()
     }
 }
+
+impl<P: GpioteOutputPin> GpioteOutputPin for &P {
+    fn pin(&self) -> u8 {
+        P::pin(*self)
+    }
+    fn port(&self) -> Port {
+        P::port(*self)
+    }
+}
@@ -76,6 +76,27 @@ impl I2S {
         Self { i2s }
     }
 
+    /// Takes ownership of the raw I2S peripheral, returning a safe wrapper in controller mode
+    /// running at the sample rate closest to `lrck_hz`.
+    ///
+    /// Returns the applied configuration along with the actual sample rate, see
+    /// [`SampleRate::closest`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_controller_with_rate(
+        i2s: I2S_PAC,
+        mck_pin: Option<&Pin<Output<PushPull>>>,
+        sck_pin: &Pin<Output<PushPull>>,
+        lrck_pin: &Pin<Output<PushPull>>,
+        sdin_pin: Option<&Pin<Input<Floating>>>,
+        sdout_pin: Option<&Pin<Output<PushPull>>>,
+        lrck_hz: u32,
+        width: SampleWidth,
+    ) -> (Self, SampleRate) {
+        let i2s = Self::new_controller(i2s, mck_pin, sck_pin, lrck_pin, sdin_pin, sdout_pin);
+        let rate = i2s.set_sample_rate(lrck_hz, width);
+        (i2s, rate)
+    }
+
     /// Takes ownership of the raw I2S peripheral, returning a safe wrapper in peripheral mode.
     pub fn new_peripheral(
         i2s: I2S_PAC,
@@ -193,6 +214,17 @@ impl I2S {
         self
     }
 
+    /// Sets the MCK frequency, ratio and sample width closest to a sample rate of `lrck_hz`.
+    ///
+    /// Returns the applied configuration, see [`SampleRate::closest`].
+    pub fn set_sample_rate(&self, lrck_hz: u32, width: SampleWidth) -> SampleRate {
+        let rate = SampleRate::closest(lrck_hz, width);
+        self.set_mck_frequency(rate.mck_freq)
+            .set_ratio(rate.ratio)
+            .set_sample_width(rate.sample_width);
+        rate
+    }
+
     /// Sets sample width.
     #[inline(always)]
     pub fn set_sample_width(&self, width: SampleWidth) -> &Self {
@@ -350,6 +382,77 @@ impl I2S {
         })
     }
 
+    /// Continuously transmits from two alternating `buffers`.
+    ///
+    /// Both buffers must be filled, of equal length, 4 byte aligned and located
+    /// in RAM. Transmission starts right away. Each time the I2S has latched the
+    /// next buffer, the one that has been played is handed back for refilling,
+    /// see [`TxStream::process`].
+    pub fn tx_stream<W>(self, buffers: [&'static mut [W]; 2]) -> Result<TxStream<W>, Error>
+    where
+        W: SupportedWordSize,
+    {
+        let maxcnt = stream_maxcnt(&buffers)?;
+        if buffers
+            .iter()
+            .any(|b| (b.as_ptr() as usize) < SRAM_LOWER || (b.as_ptr() as usize) > SRAM_UPPER)
+        {
+            return Err(Error::DMABufferNotInDataMemory);
+        }
+
+        self.i2s
+            .txd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffers[0].as_ptr() as u32) });
+        self.i2s.rxtxd.maxcnt.write(|w| unsafe { w.bits(maxcnt) });
+        self.reset_event(I2SEvent::TxPtrUpdated);
+        self.reset_event(I2SEvent::Stopped);
+        self.set_tx_enabled(true).set_rx_enabled(false);
+
+        // Conservative compiler fence to prevent starting the transfer before
+        // the buffers have been written.
+        compiler_fence(Ordering::SeqCst);
+        self.start();
+
+        Ok(TxStream {
+            inner: Some(StreamInner { i2s: self, buffers }),
+            current: 0,
+            started: false,
+            other: Slot::Ready,
+        })
+    }
+
+    /// Continuously receives into two alternating `buffers`.
+    ///
+    /// Both buffers must be of equal length and 4 byte aligned. Reception starts
+    /// right away. Each time the I2S has latched the next buffer, the one that
+    /// has been filled is handed out, see [`RxStream::process`].
+    pub fn rx_stream<W>(self, buffers: [&'static mut [W]; 2]) -> Result<RxStream<W>, Error>
+    where
+        W: SupportedWordSize,
+    {
+        let maxcnt = stream_maxcnt(&buffers)?;
+
+        self.i2s
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffers[0].as_ptr() as u32) });
+        self.i2s.rxtxd.maxcnt.write(|w| unsafe { w.bits(maxcnt) });
+        self.reset_event(I2SEvent::RxPtrUpdated);
+        self.reset_event(I2SEvent::Stopped);
+        self.set_rx_enabled(true).set_tx_enabled(false);
+
+        compiler_fence(Ordering::SeqCst);
+        self.start();
+
+        Ok(RxStream {
+            inner: Some(StreamInner { i2s: self, buffers }),
+            current: 0,
+            started: false,
+            other: Slot::Ready,
+        })
+    }
+
     /// Sets the transmit buffer RAM start address.
     #[inline(always)]
     pub fn set_tx_ptr(&self, addr: u32) -> Result<(), Error> {
@@ -465,6 +568,10 @@ pub enum Error {
     BufferTooLong,
     BuffersDontMatch,
     BufferMisaligned,
+    /// No buffer was queued in time, so the previous one has been transmitted again.
+    Underrun,
+    /// No buffer was released in time, so received data has been overwritten.
+    Overrun,
 }
 
 /// I2S Mode
@@ -497,6 +604,43 @@ impl From<MckFreq> for u32 {
     }
 }
 
+impl MckFreq {
+    /// Returns the divisor of the 32 MHz clock.
+    fn divisor(self) -> u32 {
+        match self {
+            MckFreq::_32MDiv8 => 8,
+            MckFreq::_32MDiv10 => 10,
+            MckFreq::_32MDiv11 => 11,
+            MckFreq::_32MDiv15 => 15,
+            MckFreq::_32MDiv16 => 16,
+            MckFreq::_32MDiv21 => 21,
+            MckFreq::_32MDiv23 => 23,
+            MckFreq::_32MDiv30 => 30,
+            MckFreq::_32MDiv31 => 31,
+            MckFreq::_32MDiv32 => 32,
+            MckFreq::_32MDiv42 => 42,
+            MckFreq::_32MDiv63 => 63,
+            MckFreq::_32MDiv125 => 125,
+        }
+    }
+}
+
+const MCK_FREQS: [MckFreq; 13] = [
+    MckFreq::_32MDiv8,
+    MckFreq::_32MDiv10,
+    MckFreq::_32MDiv11,
+    MckFreq::_32MDiv15,
+    MckFreq::_32MDiv16,
+    MckFreq::_32MDiv21,
+    MckFreq::_32MDiv23,
+    MckFreq::_32MDiv30,
+    MckFreq::_32MDiv31,
+    MckFreq::_32MDiv32,
+    MckFreq::_32MDiv42,
+    MckFreq::_32MDiv63,
+    MckFreq::_32MDiv125,
+];
+
 /// MCK / LRCK ratio.
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum Ratio {
@@ -516,6 +660,35 @@ impl From<Ratio> for u8 {
     }
 }
 
+impl Ratio {
+    /// Returns the number of MCK periods per LRCK period.
+    fn value(self) -> u32 {
+        match self {
+            Ratio::_32x => 32,
+            Ratio::_48x => 48,
+            Ratio::_64x => 64,
+            Ratio::_96x => 96,
+            Ratio::_128x => 128,
+            Ratio::_192x => 192,
+            Ratio::_256x => 256,
+            Ratio::_384x => 384,
+            Ratio::_512x => 512,
+        }
+    }
+}
+
+const RATIOS: [Ratio; 9] = [
+    Ratio::_32x,
+    Ratio::_48x,
+    Ratio::_64x,
+    Ratio::_96x,
+    Ratio::_128x,
+    Ratio::_192x,
+    Ratio::_256x,
+    Ratio::_384x,
+    Ratio::_512x,
+];
+
 /// Sample width.
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum SampleWidth {
@@ -529,6 +702,62 @@ impl From<SampleWidth> for u8 {
     }
 }
 
+/// A MCK frequency and ratio combination producing a sample rate.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct SampleRate {
+    pub mck_freq: MckFreq,
+    pub ratio: Ratio,
+    pub sample_width: SampleWidth,
+    /// Resulting LRCK frequency in Hz, rounded to the nearest integer.
+    pub actual_hz: u32,
+    /// Deviation of the resulting LRCK frequency from the requested one, in ppm.
+    pub error_ppm: i32,
+}
+
+impl SampleRate {
+    /// Searches the MCK frequency and ratio combinations for the sample rate
+    /// closest to `lrck_hz`, e.g. 44_100, 48_000 or 16_000.
+    ///
+    /// Only ratios that fit a frame of two `width` samples are considered;
+    /// 24-bit samples require a ratio that is a multiple of 48.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lrck_hz` is 0.
+    pub fn closest(lrck_hz: u32, width: SampleWidth) -> Self {
+        assert!(lrck_hz > 0);
+
+        let target = i64::from(lrck_hz);
+        let mut best: Option<SampleRate> = None;
+        for &ratio in RATIOS.iter() {
+            // The frame must hold two samples, i.e. 2 * width bits.
+            let (min_ratio, multiple_of) = match width {
+                SampleWidth::_8bit => (16, 1),
+                SampleWidth::_16bit => (32, 1),
+                SampleWidth::_24bit => (48, 48),
+            };
+            if ratio.value() < min_ratio || ratio.value() % multiple_of != 0 {
+                continue;
+            }
+            for &mck_freq in MCK_FREQS.iter() {
+                let divisor = i64::from(mck_freq.divisor() * ratio.value());
+                let error_ppm = (32_000_000_000_000 / divisor - target * 1_000_000) / target;
+                if best.map_or(true, |b| error_ppm.abs() < i64::from(b.error_ppm).abs()) {
+                    best = Some(SampleRate {
+                        mck_freq,
+                        ratio,
+                        sample_width: width,
+                        actual_hz: ((32_000_000 + divisor / 2) / divisor) as u32,
+                        error_ppm: error_ppm.max(i64::from(i32::MIN)).min(i64::from(i32::MAX))
+                            as i32,
+                    });
+                }
+            }
+        }
+        best.expect("every sample width has a valid ratio")
+    }
+}
+
 /// Alignment of sample within a frame.
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum Align {
@@ -650,6 +879,324 @@ impl<TxB, RxB> Drop for TransferFullDuplex<TxB, RxB> {
     }
 }
 
+/// Returns the MAXCNT value of the stream `buffers`.
+fn stream_maxcnt<W>(buffers: &[&'static mut [W]; 2]) -> Result<u32, Error> {
+    if buffers[0].len() != buffers[1].len() {
+        return Err(Error::BuffersDontMatch);
+    }
+    if buffers.iter().any(|b| b.as_ptr() as u32 % 4 != 0) {
+        return Err(Error::BufferMisaligned);
+    }
+    let maxcnt =
+        (buffers[0].len() / (core::mem::size_of::<u32>() / core::mem::size_of::<W>())) as u32;
+    if maxcnt > MAX_DMA_MAXCNT {
+        return Err(Error::BufferTooLong);
+    }
+    Ok(maxcnt)
+}
+
+/// State of the stream buffer not currently in use by the I2S.
+#[derive(Clone, Copy, PartialEq)]
+enum Slot {
+    /// Held by the application.
+    Free,
+    /// Ready, but its pointer can't be written until the first buffer has been latched.
+    Ready,
+    /// Its pointer has been written and will be latched when the current buffer ends.
+    Queued,
+}
+
+/// The I2S and buffers of a stream.
+struct StreamInner<W: 'static> {
+    i2s: I2S,
+    buffers: [&'static mut [W]; 2],
+}
+
+/// Stops a stream whose buffers are handed over on `event`.
+fn stop_stream(i2s: &I2S, event: I2SEvent) {
+    i2s.disable_interrupt(event);
+    i2s.stop();
+    i2s.reset_event(I2SEvent::Stopped);
+    i2s.reset_event(event);
+    compiler_fence(Ordering::Acquire);
+}
+
+/// A continuous double-buffered transmission, see [`I2S::tx_stream`].
+///
+/// Dropping it stops the transmission.
+pub struct TxStream<W: 'static> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<StreamInner<W>>,
+    // Index of the buffer being transmitted.
+    current: usize,
+    // The first buffer has been latched.
+    started: bool,
+    other: Slot,
+}
+
+impl<W> TxStream<W> {
+    #[inline(always)]
+    fn inner(&self) -> &StreamInner<W> {
+        self.inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+    }
+
+    /// Enables the `TxPtrUpdated` interrupt that drives [`TxStream::process`].
+    pub fn enable_interrupt(&mut self) {
+        self.inner().i2s.enable_interrupt(I2SEvent::TxPtrUpdated);
+    }
+
+    /// Disables the `TxPtrUpdated` interrupt.
+    pub fn disable_interrupt(&mut self) {
+        self.inner().i2s.disable_interrupt(I2SEvent::TxPtrUpdated);
+    }
+
+    /// Handles a pending `TxPtrUpdated` event.
+    ///
+    /// Returns `Ok(true)` if a buffer has been played and can be refilled
+    /// through [`TxStream::free_buffer`]. Returns `Err(Error::Underrun)` if the
+    /// free buffer had not been submitted in time; the current buffer is then
+    /// played again.
+    ///
+    /// Call this from the I2S interrupt handler, or poll it.
+    pub fn process(&mut self) -> Result<bool, Error> {
+        if !self.inner().i2s.is_event_triggered(I2SEvent::TxPtrUpdated) {
+            return Ok(false);
+        }
+        self.inner().i2s.reset_event(I2SEvent::TxPtrUpdated);
+
+        let mut result = Ok(false);
+        if !self.started {
+            self.started = true;
+        } else if self.other == Slot::Queued {
+            self.current ^= 1;
+            self.other = Slot::Free;
+            result = Ok(true);
+        } else {
+            result = Err(Error::Underrun);
+        }
+
+        if self.other == Slot::Ready {
+            self.queue();
+        }
+        result
+    }
+
+    /// Handles a pending `TxPtrUpdated` event, calling `f` to refill the
+    /// freed buffer and submitting it.
+    ///
+    /// Returns `true` if `f` has been called. See [`TxStream::process`]; on
+    /// an underrun, the free buffer is still refilled and submitted before the
+    /// error is returned.
+    pub fn process_with<F>(&mut self, f: F) -> Result<bool, Error>
+    where
+        F: FnOnce(&mut [W]),
+    {
+        let result = self.process();
+        let called = match self.free_buffer() {
+            Some(buffer) => {
+                f(buffer);
+                self.submit();
+                true
+            }
+            None => false,
+        };
+        result.map(|_| called)
+    }
+
+    /// Returns the buffer that can be refilled, if any.
+    pub fn free_buffer(&mut self) -> Option<&mut [W]> {
+        let free = self.current ^ 1;
+        match (self.other, self.inner.as_mut()) {
+            (Slot::Free, Some(inner)) => Some(&mut inner.buffers[free][..]),
+            _ => None,
+        }
+    }
+
+    /// Queues the refilled free buffer for transmission after the current one.
+    ///
+    /// Returns `false` if there is no free buffer.
+    pub fn submit(&mut self) -> bool {
+        if self.other != Slot::Free {
+            return false;
+        }
+        self.queue();
+        true
+    }
+
+    fn queue(&mut self) {
+        // Conservative compiler fence to make sure the buffer is written
+        // before the DMA can read it.
+        compiler_fence(Ordering::SeqCst);
+        let inner = self.inner();
+        let ptr = inner.buffers[self.current ^ 1].as_ptr() as u32;
+        inner
+            .i2s
+            .i2s
+            .txd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(ptr) });
+        self.other = Slot::Queued;
+    }
+
+    /// Stops the stream and returns the I2S and the buffers.
+    pub fn stop(mut self) -> (I2S, [&'static mut [W]; 2]) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        stop_stream(&inner.i2s, I2SEvent::TxPtrUpdated);
+        (inner.i2s, inner.buffers)
+    }
+}
+
+impl<W> Drop for TxStream<W> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_ref() {
+            stop_stream(&inner.i2s, I2SEvent::TxPtrUpdated);
+        }
+    }
+}
+
+/// A continuous double-buffered reception, see [`I2S::rx_stream`].
+///
+/// Dropping it stops the reception.
+pub struct RxStream<W: 'static> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<StreamInner<W>>,
+    // Index of the buffer being received into.
+    current: usize,
+    // The first buffer has been latched.
+    started: bool,
+    other: Slot,
+}
+
+impl<W> RxStream<W> {
+    #[inline(always)]
+    fn inner(&self) -> &StreamInner<W> {
+        self.inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+    }
+
+    /// Enables the `RxPtrUpdated` interrupt that drives [`RxStream::process`].
+    pub fn enable_interrupt(&mut self) {
+        self.inner().i2s.enable_interrupt(I2SEvent::RxPtrUpdated);
+    }
+
+    /// Disables the `RxPtrUpdated` interrupt.
+    pub fn disable_interrupt(&mut self) {
+        self.inner().i2s.disable_interrupt(I2SEvent::RxPtrUpdated);
+    }
+
+    /// Handles a pending `RxPtrUpdated` event.
+    ///
+    /// Returns `Ok(true)` if a buffer has been filled and can be read through
+    /// [`RxStream::filled_buffer`]. Returns `Err(Error::Overrun)` if the
+    /// filled buffer had not been released in time; the current buffer is then
+    /// overwritten.
+    ///
+    /// Call this from the I2S interrupt handler, or poll it.
+    pub fn process(&mut self) -> Result<bool, Error> {
+        if !self.inner().i2s.is_event_triggered(I2SEvent::RxPtrUpdated) {
+            return Ok(false);
+        }
+        self.inner().i2s.reset_event(I2SEvent::RxPtrUpdated);
+
+        let mut result = Ok(false);
+        if !self.started {
+            self.started = true;
+        } else if self.other == Slot::Queued {
+            // Conservative compiler fence to prevent optimizations that do not
+            // take in to account actions by DMA.
+            compiler_fence(Ordering::SeqCst);
+            self.current ^= 1;
+            self.other = Slot::Free;
+            result = Ok(true);
+        } else {
+            result = Err(Error::Overrun);
+        }
+
+        if self.other == Slot::Ready {
+            self.queue();
+        }
+        result
+    }
+
+    /// Handles a pending `RxPtrUpdated` event, calling `f` with the filled
+    /// buffer and releasing it.
+    ///
+    /// Returns `true` if `f` has been called. See [`RxStream::process`]; on
+    /// an overrun, the filled buffer is still passed to `f` and released before
+    /// the error is returned.
+    pub fn process_with<F>(&mut self, f: F) -> Result<bool, Error>
+    where
+        F: FnOnce(&[W]),
+    {
+        let result = self.process();
+        let called = match self.filled_buffer() {
+            Some(buffer) => {
+                f(buffer);
+                self.release();
+                true
+            }
+            None => false,
+        };
+        result.map(|_| called)
+    }
+
+    /// Returns the buffer that has been filled, if any.
+    pub fn filled_buffer(&self) -> Option<&[W]> {
+        match self.other {
+            Slot::Free => Some(&self.inner().buffers[self.current ^ 1][..]),
+            _ => None,
+        }
+    }
+
+    /// Queues the filled buffer for reception after the current one.
+    ///
+    /// Returns `false` if there is no filled buffer.
+    pub fn release(&mut self) -> bool {
+        if self.other != Slot::Free {
+            return false;
+        }
+        self.queue();
+        true
+    }
+
+    fn queue(&mut self) {
+        compiler_fence(Ordering::SeqCst);
+        let inner = self.inner();
+        let ptr = inner.buffers[self.current ^ 1].as_ptr() as u32;
+        inner
+            .i2s
+            .i2s
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(ptr) });
+        self.other = Slot::Queued;
+    }
+
+    /// Stops the stream and returns the I2S and the buffers.
+    pub fn stop(mut self) -> (I2S, [&'static mut [W]; 2]) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        stop_stream(&inner.i2s, I2SEvent::RxPtrUpdated);
+        (inner.i2s, inner.buffers)
+    }
+}
+
+impl<W> Drop for RxStream<W> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_ref() {
+            stop_stream(&inner.i2s, I2SEvent::RxPtrUpdated);
+        }
+    }
+}
+
 pub trait SupportedWordSize: private::Sealed {}
 impl private::Sealed for i8 {}
 impl SupportedWordSize for i8 {}
@@ -0,0 +1,475 @@
+//! Interrupt-driven IEEE 802.15.4 radio driver
+
+use core::array;
+
+use crate::{
+    fem::{Direction, FrontEnd, NoFem, Trigger},
+    pac::radio::state::STATE_A,
+    ppi::ConfigurablePpi,
+    timer,
+};
+
+use super::{
+    ack_requested, dma_end_fence, dma_start_fence, write_ack, Error, Packet, Radio, RAMP_UP_US,
+    TURNAROUND_TIME_US,
+};
+
+/// TIMER capture register the start of received frames is captured into
+const TIMESTAMP_CC: usize = 2;
+
+/// Non-blocking IEEE 802.15.4 radio driver
+///
+/// Unlike the methods of `Radio`, which wait for the RADIO to finish, the `Driver` is a state
+/// machine advanced by `process`, which is meant to be called from the RADIO interrupt handler
+/// (or polled, or awaited through `next_event` with the `async` feature, see `on_interrupt`).
+/// Frames passed to `transmit` are queued and sent in order, after Clear Channel Assessment;
+/// while receiving is enabled, received frames are stored in a ring of `RX` frames until they
+/// are `read`. The settings of the `Radio`, including its address filter and automatic ACKs,
+/// are applied.
+///
+/// Each received frame is timestamped with the value of a free-running 1 MHz TIMER at the start
+/// of the frame, captured in hardware through a PPI channel.
+///
+/// All transfers use the `buffer` given to `new`, which must stay valid while the RADIO is
+/// running, hence `&'static mut`.
+///
+/// The amplifiers of the front-end module of the `Radio`, if any, are switched like `Radio`
+/// does, except that the PA is switched on for an ACK only once `process` handles the end of
+/// the received frame.
+pub struct Driver<'c, T, P, const TX: usize, const RX: usize, F = NoFem> {
+    radio: Radio<'c, F>,
+    timer: T,
+    ppi: P,
+    buffer: &'static mut Packet,
+    phase: Phase,
+    receiving: bool,
+    tx_queue: Ring<Packet, TX>,
+    rx_ring: Ring<RxFrame, RX>,
+}
+
+/// A received frame
+#[derive(Clone)]
+pub struct RxFrame {
+    /// The frame, see `Packet::lqi` for its link quality
+    pub packet: Packet,
+    /// The value of the timestamp TIMER (in microseconds) when the PHY header of the frame was
+    /// received
+    pub timestamp: u32,
+}
+
+/// An event reported by `Driver::process`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DriverEvent {
+    /// A frame has been received and can be `read`
+    Received,
+    /// A frame has been received, but dropped because the receive ring is full
+    Overflow,
+    /// The oldest frame of the transmit queue has been sent
+    Transmitted,
+    /// The oldest frame of the transmit queue has been dropped because the channel was busy
+    ChannelBusy,
+}
+
+/// What the RADIO is doing; every phase but `Idle` ends with the DISABLED event
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Idle,
+    Rx { auto_ack: bool },
+    Tx,
+    Ack,
+    Abort,
+}
+
+impl<'c, T, P, const TX: usize, const RX: usize, F> Driver<'c, T, P, TX, RX, F>
+where
+    T: timer::Instance,
+    P: ConfigurablePpi,
+    F: FrontEnd,
+{
+    /// Takes control of the `radio`, using `timer` and the `ppi` channel to timestamp received
+    /// frames
+    ///
+    /// The `timer` is configured to run freely at 1 MHz.
+    pub fn new(mut radio: Radio<'c, F>, timer: T, mut ppi: P, buffer: &'static mut Packet) -> Self {
+        radio.radio.shorts.reset();
+        radio.disable();
+        radio.radio.events_disabled.reset();
+
+        let t = timer.as_timer0();
+        t.tasks_stop.write(|w| unsafe { w.bits(1) });
+        t.shorts.reset();
+        t.prescaler.write(|w| unsafe { w.prescaler().bits(4) }); // 1 MHz
+        t.bitmode.write(|w| w.bitmode()._32bit());
+        t.tasks_clear.write(|w| unsafe { w.bits(1) });
+        t.tasks_start.write(|w| unsafe { w.bits(1) });
+
+        ppi.set_event_endpoint(&radio.radio.events_framestart);
+        ppi.set_task_endpoint(&t.tasks_capture[TIMESTAMP_CC]);
+        ppi.enable();
+
+        Self {
+            radio,
+            timer,
+            ppi,
+            buffer,
+            phase: Phase::Idle,
+            receiving: false,
+            tx_queue: Ring::new(array::from_fn(|_| Packet::new())),
+            rx_ring: Ring::new(array::from_fn(|_| RxFrame {
+                packet: Packet::new(),
+                timestamp: 0,
+            })),
+        }
+    }
+
+    /// Returns the current value of the timestamp TIMER, in microseconds
+    pub fn now(&self) -> u32 {
+        self.timer.read_counter()
+    }
+
+    /// Starts listening for frames whenever no frame is being sent
+    pub fn start_receiving(&mut self) {
+        self.receiving = true;
+        if self.phase == Phase::Idle {
+            self.resume();
+        }
+    }
+
+    /// Stops listening for frames; a frame that is being received is dropped
+    pub fn stop_receiving(&mut self) {
+        self.receiving = false;
+        if let Phase::Rx { .. } = self.phase {
+            self.abort();
+        }
+    }
+
+    /// Queues `packet` for transmission
+    ///
+    /// If the RADIO is listening and not in the middle of receiving a frame, listening is
+    /// interrupted to send the frame right away; otherwise it is sent once the current operation
+    /// completes. Returns the `QueueFull` error if `TX` frames are already queued.
+    pub fn transmit(&mut self, packet: &Packet) -> Result<(), Error> {
+        self.tx_queue
+            .push_with(|slot| slot.clone_from(packet))
+            .map_err(|_| Error::QueueFull)?;
+
+        match self.phase {
+            Phase::Idle => self.resume(),
+            Phase::Rx { .. } if self.radio.radio.events_framestart.read().bits() == 0 => {
+                self.abort()
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of frames waiting to be sent, including the one being sent
+    pub fn tx_queue_len(&self) -> usize {
+        self.tx_queue.len
+    }
+
+    /// Removes the oldest received frame from the receive ring and returns it
+    pub fn read(&mut self) -> Option<RxFrame> {
+        self.rx_ring.pop()
+    }
+
+    /// Enables the interrupt that drives the state machine (the DISABLED event)
+    ///
+    /// `process` must then be called from the RADIO interrupt handler.
+    pub fn enable_interrupt(&mut self) {
+        self.radio.radio.intenset.write(|w| w.disabled().set());
+    }
+
+    /// Disables the interrupt that drives the state machine
+    pub fn disable_interrupt(&mut self) {
+        self.radio.radio.intenclr.write(|w| w.disabled().clear());
+    }
+
+    /// Advances the state machine if the RADIO has finished its current operation
+    ///
+    /// Received frames are stored in the receive ring, queued frames are sent, and the event
+    /// that occurred, if any, is returned.
+    pub fn process(&mut self) -> Option<DriverEvent> {
+        if self.radio.radio.events_disabled.read().bits() == 0 {
+            return None;
+        }
+        self.radio.radio.events_disabled.reset();
+        dma_end_fence();
+
+        let event = match self.phase {
+            Phase::Idle => return None,
+            // a DISABLED event left over from an abort does not complete the operation
+            Phase::Rx { .. } if self.radio.radio.events_end.read().bits() == 0 => return None,
+            Phase::Tx
+                if self.radio.radio.events_phyend.read().bits() == 0
+                    && self.radio.radio.events_ccabusy.read().bits() == 0 =>
+            {
+                return None
+            }
+            Phase::Rx { auto_ack } => {
+                self.radio.radio.events_end.reset();
+                let (event, ack) = self.finish_rx();
+                if auto_ack {
+                    // the RADIO is already ramping up for the ACK; send it or cancel it
+                    match ack {
+                        Some(sequence_number) => self.send_ack(sequence_number),
+                        None => self.abort(),
+                    }
+                    return event;
+                }
+                event
+            }
+            Phase::Tx => {
+                let busy = self.radio.radio.events_ccabusy.read().bits() != 0;
+                self.radio.radio.events_ccabusy.reset();
+                self.radio.radio.events_phyend.reset();
+                self.tx_queue.pop();
+                Some(if busy {
+                    DriverEvent::ChannelBusy
+                } else {
+                    DriverEvent::Transmitted
+                })
+            }
+            Phase::Ack | Phase::Abort => None,
+        };
+
+        self.resume();
+        event
+    }
+
+    /// Stops the RADIO and the timestamp TIMER and returns the resources
+    ///
+    /// Frames that are still queued or in the receive ring are dropped.
+    pub fn free(mut self) -> (Radio<'c, F>, T, P, &'static mut Packet) {
+        self.disable_interrupt();
+        self.radio.radio.shorts.reset();
+        self.radio.disable();
+        self.radio.radio.events_disabled.reset();
+        self.ppi.disable();
+        self.timer
+            .as_timer0()
+            .tasks_stop
+            .write(|w| unsafe { w.bits(1) });
+        (self.radio, self.timer, self.ppi, self.buffer)
+    }
+
+    /// Stores the frame received in `buffer`; returns the event and, if the frame is to be
+    /// acknowledged, its sequence number
+    fn finish_rx(&mut self) -> (Option<DriverEvent>, Option<u8>) {
+        let radio = &self.radio;
+        if radio.radio.crcstatus.read().crcstatus().bit_is_clear() || !radio.accepts(&*self.buffer)
+        {
+            return (None, None);
+        }
+
+        let timestamp = self.timer.as_timer0().cc[TIMESTAMP_CC].read().bits();
+        let buffer = &*self.buffer;
+        let stored = self
+            .rx_ring
+            .push_with(|frame| {
+                frame.packet.clone_from(buffer);
+                frame.timestamp = timestamp;
+            })
+            .is_ok();
+
+        if stored {
+            (Some(DriverEvent::Received), ack_requested(buffer))
+        } else {
+            // not acknowledged so the sender retransmits it
+            (Some(DriverEvent::Overflow), None)
+        }
+    }
+
+    /// Replaces the received frame in `buffer` with its ACK and lets the RADIO send it
+    fn send_ack(&mut self, sequence_number: u8) {
+        let pending = self.radio.frame_pending.map_or(false, |f| f(&*self.buffer));
+        write_ack(self.buffer, sequence_number, pending);
+
+        // the transmitter is ramping up; TIFS delays the ACK until `aTurnaroundTime` after the
+        // received frame. How much of the ramp-up is left is unknown, so the PA is switched on
+        // right away.
+        self.radio.enable_fem(Direction::Tx, 0, Trigger::Now);
+        dma_start_fence();
+        let radio = &self.radio.radio;
+        radio
+            .shorts
+            .write(|w| w.txready_start().set_bit().phyend_disable().set_bit());
+        // if the ramp-up completed before the shortcut was armed, start the ACK right away
+        if radio.state.read().state() == STATE_A::TXIDLE {
+            radio.tasks_start.write(|w| w.tasks_start().set_bit());
+        }
+        self.phase = Phase::Ack;
+    }
+
+    /// Cancels the current operation; the DISABLED event follows
+    fn abort(&mut self) {
+        self.radio.radio.shorts.reset();
+        self.radio
+            .radio
+            .tasks_disable
+            .write(|w| w.tasks_disable().set_bit());
+        self.phase = Phase::Abort;
+    }
+
+    /// Starts the next operation from the DISABLED state: sends the oldest queued frame, or
+    /// listens if receiving is enabled
+    fn resume(&mut self) {
+        // settings are picked up when the RADIO is enabled, which all operations below do
+        self.radio.needs_enable = false;
+        let radio = &self.radio.radio;
+        radio.events_disabled.reset();
+
+        if let Some(packet) = self.tx_queue.peek() {
+            self.buffer.clone_from(packet);
+            radio.events_ccabusy.reset();
+            radio.events_phyend.reset();
+
+            // CCA, then either send the frame or give up if the channel is busy
+            radio.shorts.write(|w| {
+                w.rxready_ccastart()
+                    .set_bit()
+                    .ccaidle_txen()
+                    .set_bit()
+                    .txready_start()
+                    .set_bit()
+                    .ccabusy_disable()
+                    .set_bit()
+                    .phyend_disable()
+                    .set_bit()
+            });
+            self.phase = Phase::Tx;
+        } else if self.receiving {
+            let auto_ack = self.radio.auto_ack;
+            radio.events_end.reset();
+            radio.events_framestart.reset();
+
+            if auto_ack {
+                radio.tifs.write(|w| unsafe { w.bits(TURNAROUND_TIME_US) });
+            }
+            // once a frame has been received the RADIO is disabled and, for automatic ACKs,
+            // turned around to TX right away
+            radio.shorts.write(|w| {
+                w.rxready_start()
+                    .set_bit()
+                    .end_disable()
+                    .set_bit()
+                    .disabled_txen()
+                    .bit(auto_ack)
+            });
+            self.phase = Phase::Rx { auto_ack };
+        } else {
+            radio.shorts.reset();
+            self.radio.disable_fem();
+            self.phase = Phase::Idle;
+            return;
+        }
+
+        // the LNA is switched on for CCA too, and the CCAIDLE event switches to the PA
+        self.radio
+            .enable_fem(Direction::Rx, RAMP_UP_US, Trigger::Now);
+        if self.phase == Phase::Tx {
+            self.radio
+                .enable_fem(Direction::Tx, RAMP_UP_US, Trigger::CcaIdle);
+        }
+
+        let radio = &self.radio.radio;
+        // NOTE(unsafe) DMA transfer has not yet started
+        unsafe {
+            radio
+                .packetptr
+                .write(|w| w.packetptr().bits(self.buffer.buffer.as_ptr() as u32));
+        }
+        dma_start_fence();
+        radio.tasks_rxen.write(|w| w.tasks_rxen().set_bit());
+    }
+}
+
+#[cfg(feature = "async")]
+static WAKER: crate::asynch::AtomicWaker = crate::asynch::AtomicWaker::new();
+
+/// Handles the RADIO interrupt for the async API of the `Driver`.
+///
+/// This must be called from the RADIO interrupt handler when using `Driver::next_event`, and the
+/// interrupt must be unmasked in the NVIC.
+#[cfg(feature = "async")]
+pub fn on_interrupt() {
+    let radio = unsafe { &*crate::pac::RADIO::ptr() };
+
+    // The awaiting future advances the state machine and re-enables the interrupt as needed.
+    radio.intenclr.write(|w| w.disabled().clear());
+    WAKER.wake();
+}
+
+#[cfg(feature = "async")]
+impl<'c, T, P, const TX: usize, const RX: usize, F> Driver<'c, T, P, TX, RX, F>
+where
+    T: timer::Instance,
+    P: ConfigurablePpi,
+    F: FrontEnd,
+{
+    /// Advances the state machine until an event occurs, without blocking.
+    ///
+    /// Frames keep being received and sent while the future is not polled, but only up to the
+    /// end of the current operation.
+    pub async fn next_event(&mut self) -> DriverEvent {
+        core::future::poll_fn(|cx| {
+            WAKER.register(cx.waker());
+
+            loop {
+                match self.process() {
+                    Some(event) => return core::task::Poll::Ready(event),
+                    // the operation that ended did not produce an event; check the next one
+                    None if self.radio.radio.events_disabled.read().bits() != 0 => {}
+                    None => break,
+                }
+            }
+
+            self.enable_interrupt();
+            core::task::Poll::Pending
+        })
+        .await
+    }
+}
+
+/// Fixed-capacity FIFO
+struct Ring<T, const N: usize> {
+    items: [T; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Clone, const N: usize> Ring<T, N> {
+    fn new(items: [T; N]) -> Self {
+        Self {
+            items,
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Appends an item by filling the next slot in place
+    fn push_with(&mut self, f: impl FnOnce(&mut T)) -> Result<(), ()> {
+        if self.len == N {
+            return Err(());
+        }
+        f(&mut self.items[(self.head + self.len) % N]);
+        self.len += 1;
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&T> {
+        if self.len == 0 {
+            None
+        } else {
+            Some(&self.items[self.head])
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        let item = self.peek()?.clone();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(item)
+    }
+}
@@ -0,0 +1,750 @@
+//! IEEE 802.15.4 MAC frames
+//!
+//! `Frame` parses the MAC header of a received `Packet` without copying its payload, and
+//! `FrameBuilder` writes data, ACK, beacon and MAC command frames into a `Packet`.
+//!
+//! See section 7.2 of IEEE 802.15.4-2015 for the frame format. Information Elements are not
+//! parsed; they are part of the payload.
+
+use super::Packet;
+
+/// Type of a MAC frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameType {
+    /// Beacon
+    Beacon,
+    /// Data
+    Data,
+    /// Acknowledgment
+    Ack,
+    /// MAC command
+    MacCommand,
+    /// Reserved value
+    Reserved,
+    /// Multipurpose (IEEE 802.15.4-2015)
+    Multipurpose,
+    /// Fragment or Frak (IEEE 802.15.4-2015)
+    Fragment,
+    /// Extended (IEEE 802.15.4-2015)
+    Extended,
+}
+
+impl FrameType {
+    fn from_bits(bits: u16) -> Self {
+        match bits & 0b111 {
+            0 => FrameType::Beacon,
+            1 => FrameType::Data,
+            2 => FrameType::Ack,
+            3 => FrameType::MacCommand,
+            4 => FrameType::Reserved,
+            5 => FrameType::Multipurpose,
+            6 => FrameType::Fragment,
+            _ => FrameType::Extended,
+        }
+    }
+}
+
+/// Version of the standard a frame conforms to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameVersion {
+    /// IEEE 802.15.4-2003
+    Ieee2003,
+    /// IEEE 802.15.4-2006
+    Ieee2006,
+    /// IEEE 802.15.4-2015
+    Ieee2015,
+    /// Reserved value
+    Reserved,
+}
+
+/// Addressing mode of the destination or source address
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AddressMode {
+    /// No address
+    None,
+    /// Reserved value
+    Reserved,
+    /// 16-bit short address
+    Short,
+    /// 64-bit extended address
+    Extended,
+}
+
+impl AddressMode {
+    fn from_bits(bits: u16) -> Self {
+        match bits & 0b11 {
+            0 => AddressMode::None,
+            1 => AddressMode::Reserved,
+            2 => AddressMode::Short,
+            _ => AddressMode::Extended,
+        }
+    }
+}
+
+/// Short or extended device address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Address {
+    /// 16-bit short address
+    Short(u16),
+    /// 64-bit extended address
+    Extended(u64),
+}
+
+impl Address {
+    /// Broadcast short address
+    pub const BROADCAST: Address = Address::Short(0xFFFF);
+
+    /// Returns the addressing mode of this address
+    pub fn mode(&self) -> AddressMode {
+        match self {
+            Address::Short(_) => AddressMode::Short,
+            Address::Extended(_) => AddressMode::Extended,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Address::Short(_) => 2,
+            Address::Extended(_) => 8,
+        }
+    }
+}
+
+/// The frame control field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameControl(u16);
+
+impl FrameControl {
+    const FRAME_TYPE_SHIFT: u16 = 0;
+    const SECURITY_ENABLED: u16 = 1 << 3;
+    const FRAME_PENDING: u16 = 1 << 4;
+    const ACK_REQUEST: u16 = 1 << 5;
+    const PAN_ID_COMPRESSION: u16 = 1 << 6;
+    const SEQUENCE_NUMBER_SUPPRESSION: u16 = 1 << 8;
+    const IE_PRESENT: u16 = 1 << 9;
+    const DST_ADDR_MODE_SHIFT: u16 = 10;
+    const FRAME_VERSION_SHIFT: u16 = 12;
+    const SRC_ADDR_MODE_SHIFT: u16 = 14;
+
+    /// Returns a frame control field with the given frame type and all other fields cleared
+    pub fn new(frame_type: FrameType) -> Self {
+        Self((frame_type as u16) << Self::FRAME_TYPE_SHIFT)
+    }
+
+    /// Returns the frame control field with the given raw value
+    pub fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw value of the frame control field
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    /// Returns the frame type
+    pub fn frame_type(&self) -> FrameType {
+        FrameType::from_bits(self.0 >> Self::FRAME_TYPE_SHIFT)
+    }
+
+    /// Returns whether the frame is secured, and has an auxiliary security header
+    pub fn security_enabled(&self) -> bool {
+        self.0 & Self::SECURITY_ENABLED != 0
+    }
+
+    /// Returns whether the sender has more data for the recipient
+    pub fn frame_pending(&self) -> bool {
+        self.0 & Self::FRAME_PENDING != 0
+    }
+
+    /// Returns whether the recipient is requested to acknowledge the frame
+    pub fn ack_request(&self) -> bool {
+        self.0 & Self::ACK_REQUEST != 0
+    }
+
+    /// Returns whether the PAN ID compression bit is set
+    pub fn pan_id_compression(&self) -> bool {
+        self.0 & Self::PAN_ID_COMPRESSION != 0
+    }
+
+    /// Returns whether the sequence number is omitted (IEEE 802.15.4-2015 frames only)
+    pub fn sequence_number_suppression(&self) -> bool {
+        self.frame_version() == FrameVersion::Ieee2015
+            && self.0 & Self::SEQUENCE_NUMBER_SUPPRESSION != 0
+    }
+
+    /// Returns whether Information Elements follow the header (IEEE 802.15.4-2015 frames only)
+    pub fn ie_present(&self) -> bool {
+        self.frame_version() == FrameVersion::Ieee2015 && self.0 & Self::IE_PRESENT != 0
+    }
+
+    /// Returns the addressing mode of the destination address
+    pub fn dst_addr_mode(&self) -> AddressMode {
+        AddressMode::from_bits(self.0 >> Self::DST_ADDR_MODE_SHIFT)
+    }
+
+    /// Returns the frame version
+    pub fn frame_version(&self) -> FrameVersion {
+        match (self.0 >> Self::FRAME_VERSION_SHIFT) & 0b11 {
+            0 => FrameVersion::Ieee2003,
+            1 => FrameVersion::Ieee2006,
+            2 => FrameVersion::Ieee2015,
+            _ => FrameVersion::Reserved,
+        }
+    }
+
+    /// Returns the addressing mode of the source address
+    pub fn src_addr_mode(&self) -> AddressMode {
+        AddressMode::from_bits(self.0 >> Self::SRC_ADDR_MODE_SHIFT)
+    }
+
+    /// Sets the frame pending bit
+    pub fn set_frame_pending(&mut self, pending: bool) {
+        self.set(Self::FRAME_PENDING, pending);
+    }
+
+    /// Sets the acknowledgment request bit
+    pub fn set_ack_request(&mut self, request: bool) {
+        self.set(Self::ACK_REQUEST, request);
+    }
+
+    /// Sets the PAN ID compression bit
+    pub fn set_pan_id_compression(&mut self, compression: bool) {
+        self.set(Self::PAN_ID_COMPRESSION, compression);
+    }
+
+    /// Sets the security enabled bit
+    pub fn set_security_enabled(&mut self, enabled: bool) {
+        self.set(Self::SECURITY_ENABLED, enabled);
+    }
+
+    /// Sets the addressing mode of the destination address
+    pub fn set_dst_addr_mode(&mut self, mode: AddressMode) {
+        self.0 = self.0 & !(0b11 << Self::DST_ADDR_MODE_SHIFT)
+            | (mode as u16) << Self::DST_ADDR_MODE_SHIFT;
+    }
+
+    /// Sets the frame version
+    pub fn set_frame_version(&mut self, version: FrameVersion) {
+        self.0 = self.0 & !(0b11 << Self::FRAME_VERSION_SHIFT)
+            | (version as u16) << Self::FRAME_VERSION_SHIFT;
+    }
+
+    /// Sets the addressing mode of the source address
+    pub fn set_src_addr_mode(&mut self, mode: AddressMode) {
+        self.0 = self.0 & !(0b11 << Self::SRC_ADDR_MODE_SHIFT)
+            | (mode as u16) << Self::SRC_ADDR_MODE_SHIFT;
+    }
+
+    fn set(&mut self, bit: u16, value: bool) {
+        if value {
+            self.0 |= bit;
+        } else {
+            self.0 &= !bit;
+        }
+    }
+
+    /// Returns whether the destination and source PAN IDs are present, see table 7-2 of
+    /// IEEE 802.15.4-2015
+    fn pan_ids_present(&self) -> (bool, bool) {
+        let dst = self.dst_addr_mode();
+        let src = self.src_addr_mode();
+        let compression = self.pan_id_compression();
+
+        if self.frame_version() == FrameVersion::Ieee2015 {
+            match (dst, src) {
+                (AddressMode::None, AddressMode::None) => (compression, false),
+                (_, AddressMode::None) => (!compression, false),
+                (AddressMode::None, _) => (false, !compression),
+                (AddressMode::Extended, AddressMode::Extended) => (!compression, false),
+                _ => (true, !compression),
+            }
+        } else {
+            let dst = dst != AddressMode::None;
+            let src = src != AddressMode::None;
+            (dst, src && !(dst && compression))
+        }
+    }
+}
+
+/// Key identifier of the auxiliary security header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyId {
+    /// The key is determined implicitly from the originator and recipient
+    Implicit,
+    /// Key index
+    Index(u8),
+    /// 4-byte key source and key index
+    Source4 {
+        /// Key source
+        source: u32,
+        /// Key index
+        index: u8,
+    },
+    /// 8-byte key source and key index
+    Source8 {
+        /// Key source
+        source: u64,
+        /// Key index
+        index: u8,
+    },
+}
+
+impl KeyId {
+    fn mode(&self) -> u8 {
+        match self {
+            KeyId::Implicit => 0,
+            KeyId::Index(_) => 1,
+            KeyId::Source4 { .. } => 2,
+            KeyId::Source8 { .. } => 3,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            KeyId::Implicit => 0,
+            KeyId::Index(_) => 1,
+            KeyId::Source4 { .. } => 5,
+            KeyId::Source8 { .. } => 9,
+        }
+    }
+}
+
+/// Auxiliary security header (IEEE 802.15.4-2006 and later)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecurityHeader {
+    /// Security level, in the range `0..=7`
+    pub level: u8,
+    /// Key identifier
+    pub key_id: KeyId,
+    /// Frame counter; `None` if suppressed (IEEE 802.15.4-2015)
+    pub frame_counter: Option<u32>,
+}
+
+impl SecurityHeader {
+    const FRAME_COUNTER_SUPPRESSION: u8 = 1 << 5;
+
+    /// Returns whether the payload is encrypted at this security level
+    pub fn encrypted(&self) -> bool {
+        self.level & 0b100 != 0
+    }
+
+    /// Returns the length of the Message Integrity Code at the end of the frame, in bytes
+    pub fn mic_len(&self) -> usize {
+        match self.level & 0b11 {
+            0 => 0,
+            1 => 4,
+            2 => 8,
+            _ => 16,
+        }
+    }
+
+    fn len(&self) -> usize {
+        let frame_counter_len = if self.frame_counter.is_some() { 4 } else { 0 };
+        1 + frame_counter_len + self.key_id.len()
+    }
+
+    fn parse(reader: &mut Reader<'_>) -> Result<Self, FrameError> {
+        let control = reader.u8()?;
+        let frame_counter = if control & Self::FRAME_COUNTER_SUPPRESSION != 0 {
+            None
+        } else {
+            Some(reader.u32()?)
+        };
+        let key_id = match (control >> 3) & 0b11 {
+            0 => KeyId::Implicit,
+            1 => KeyId::Index(reader.u8()?),
+            2 => KeyId::Source4 {
+                source: reader.u32()?,
+                index: reader.u8()?,
+            },
+            _ => KeyId::Source8 {
+                source: reader.u64()?,
+                index: reader.u8()?,
+            },
+        };
+
+        Ok(Self {
+            level: control & 0b111,
+            key_id,
+            frame_counter,
+        })
+    }
+
+    fn write(&self, writer: &mut Writer<'_>) {
+        let mut control = (self.level & 0b111) | self.key_id.mode() << 3;
+        if self.frame_counter.is_none() {
+            control |= Self::FRAME_COUNTER_SUPPRESSION;
+        }
+        writer.u8(control);
+        if let Some(frame_counter) = self.frame_counter {
+            writer.bytes(&frame_counter.to_le_bytes());
+        }
+        match self.key_id {
+            KeyId::Implicit => {}
+            KeyId::Index(index) => writer.u8(index),
+            KeyId::Source4 { source, index } => {
+                writer.bytes(&source.to_le_bytes());
+                writer.u8(index);
+            }
+            KeyId::Source8 { source, index } => {
+                writer.bytes(&source.to_le_bytes());
+                writer.u8(index);
+            }
+        }
+    }
+}
+
+/// Frame error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FrameError {
+    /// The frame is shorter than its header
+    TooShort,
+    /// The frame uses the reserved addressing mode
+    ReservedAddressMode,
+    /// The frame does not fit in a `Packet`
+    TooLong,
+    /// The frame type is reserved or has a different header format (multipurpose, fragment
+    /// and extended frames), which is not supported
+    UnsupportedFrameType,
+}
+
+/// A MAC frame, parsed from the contents of a `Packet`
+///
+/// The header fields are decoded by `parse`; the payload and MIC are borrowed from the packet.
+#[derive(Clone, Copy, Debug)]
+pub struct Frame<'a> {
+    frame_control: FrameControl,
+    sequence_number: Option<u8>,
+    dst_pan_id: Option<u16>,
+    dst_address: Option<Address>,
+    src_pan_id: Option<u16>,
+    src_address: Option<Address>,
+    security: Option<SecurityHeader>,
+    header_len: usize,
+    payload: &'a [u8],
+    mic: &'a [u8],
+}
+
+impl<'a> Frame<'a> {
+    /// Parses the MAC header of `data`, usually a received `Packet`
+    ///
+    /// Only beacon, data, ACK and MAC command frames are supported.
+    pub fn parse(data: &'a [u8]) -> Result<Self, FrameError> {
+        let mut reader = Reader { data, pos: 0 };
+        let frame_control = FrameControl(reader.u16()?);
+        match frame_control.frame_type() {
+            FrameType::Beacon | FrameType::Data | FrameType::Ack | FrameType::MacCommand => {}
+            _ => return Err(FrameError::UnsupportedFrameType),
+        }
+
+        let sequence_number = if frame_control.sequence_number_suppression() {
+            None
+        } else {
+            Some(reader.u8()?)
+        };
+
+        let (dst_pan, src_pan) = frame_control.pan_ids_present();
+        let dst_pan_id = if dst_pan { Some(reader.u16()?) } else { None };
+        let dst_address = reader.address(frame_control.dst_addr_mode())?;
+        let src_pan_id = if src_pan { Some(reader.u16()?) } else { None };
+        let src_address = reader.address(frame_control.src_addr_mode())?;
+
+        let security = if frame_control.security_enabled() {
+            Some(SecurityHeader::parse(&mut reader)?)
+        } else {
+            None
+        };
+
+        let header_len = reader.pos;
+        let mic_len = security.map_or(0, |security| security.mic_len());
+        if data.len() < header_len + mic_len {
+            return Err(FrameError::TooShort);
+        }
+        let (payload, mic) = data[header_len..].split_at(data.len() - header_len - mic_len);
+
+        Ok(Self {
+            frame_control,
+            sequence_number,
+            dst_pan_id,
+            dst_address,
+            src_pan_id,
+            src_address,
+            security,
+            header_len,
+            payload,
+            mic,
+        })
+    }
+
+    /// Returns the frame control field
+    pub fn frame_control(&self) -> FrameControl {
+        self.frame_control
+    }
+
+    /// Returns the frame type
+    pub fn frame_type(&self) -> FrameType {
+        self.frame_control.frame_type()
+    }
+
+    /// Returns the sequence number, unless it is suppressed
+    pub fn sequence_number(&self) -> Option<u8> {
+        self.sequence_number
+    }
+
+    /// Returns the destination PAN ID, if present
+    pub fn dst_pan_id(&self) -> Option<u16> {
+        self.dst_pan_id
+    }
+
+    /// Returns the destination address, if present
+    pub fn dst_address(&self) -> Option<Address> {
+        self.dst_address
+    }
+
+    /// Returns the source PAN ID
+    ///
+    /// If the source PAN ID is elided because of PAN ID compression, the destination PAN ID is
+    /// returned.
+    pub fn src_pan_id(&self) -> Option<u16> {
+        match (self.src_pan_id, self.src_address) {
+            (None, Some(_)) => self.dst_pan_id,
+            (pan_id, _) => pan_id,
+        }
+    }
+
+    /// Returns the source address, if present
+    pub fn src_address(&self) -> Option<Address> {
+        self.src_address
+    }
+
+    /// Returns the auxiliary security header, if the frame is secured
+    pub fn security_header(&self) -> Option<SecurityHeader> {
+        self.security
+    }
+
+    /// Returns the length of the MAC header, in bytes
+    pub fn header_len(&self) -> usize {
+        self.header_len
+    }
+
+    /// Returns the MAC payload
+    ///
+    /// For MAC command frames, the payload starts with the command identifier.
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns the Message Integrity Code of a secured frame; empty otherwise
+    pub fn mic(&self) -> &'a [u8] {
+        self.mic
+    }
+}
+
+/// Writes MAC frames into a `Packet`
+///
+/// Frames are written as IEEE 802.15.4-2006 frames if they are secured, and as
+/// IEEE 802.15.4-2003 frames otherwise. The PAN ID compression bit is set, and the source PAN
+/// ID elided, if both addresses are present and the PAN IDs are equal.
+#[derive(Clone, Copy, Debug)]
+pub struct FrameBuilder {
+    frame_control: FrameControl,
+    sequence_number: u8,
+    dst: Option<(u16, Address)>,
+    src: Option<(u16, Address)>,
+    security: Option<SecurityHeader>,
+}
+
+impl FrameBuilder {
+    /// Returns a builder of a frame of the given type
+    pub fn new(frame_type: FrameType, sequence_number: u8) -> Self {
+        Self {
+            frame_control: FrameControl::new(frame_type),
+            sequence_number,
+            dst: None,
+            src: None,
+            security: None,
+        }
+    }
+
+    /// Returns a builder of a data frame
+    pub fn data(sequence_number: u8) -> Self {
+        Self::new(FrameType::Data, sequence_number)
+    }
+
+    /// Returns a builder of an ACK frame, acknowledging the frame with the given sequence number
+    pub fn ack(sequence_number: u8) -> Self {
+        Self::new(FrameType::Ack, sequence_number)
+    }
+
+    /// Returns a builder of a beacon frame
+    ///
+    /// The payload is made of the superframe specification, GTS and pending address fields,
+    /// followed by the beacon payload.
+    pub fn beacon(sequence_number: u8) -> Self {
+        Self::new(FrameType::Beacon, sequence_number)
+    }
+
+    /// Returns a builder of a MAC command frame
+    ///
+    /// The payload starts with the command identifier.
+    pub fn mac_command(sequence_number: u8) -> Self {
+        Self::new(FrameType::MacCommand, sequence_number)
+    }
+
+    /// Sets the destination PAN ID and address
+    pub fn dst(mut self, pan_id: u16, address: Address) -> Self {
+        self.dst = Some((pan_id, address));
+        self
+    }
+
+    /// Sets the source PAN ID and address
+    pub fn src(mut self, pan_id: u16, address: Address) -> Self {
+        self.src = Some((pan_id, address));
+        self
+    }
+
+    /// Sets the acknowledgment request bit
+    pub fn ack_request(mut self, request: bool) -> Self {
+        self.frame_control.set_ack_request(request);
+        self
+    }
+
+    /// Sets the frame pending bit
+    pub fn frame_pending(mut self, pending: bool) -> Self {
+        self.frame_control.set_frame_pending(pending);
+        self
+    }
+
+    /// Adds an auxiliary security header
+    ///
+    /// The payload passed to `build` must then be followed by the MIC, if any, and be encrypted
+    /// according to the security level.
+    pub fn security(mut self, header: SecurityHeader) -> Self {
+        self.security = Some(header);
+        self
+    }
+
+    /// Writes the frame, with the given `payload`, into `packet`
+    pub fn build(&self, packet: &mut Packet, payload: &[u8]) -> Result<(), FrameError> {
+        let mut frame_control = self.frame_control;
+        frame_control.set_dst_addr_mode(self.dst.map_or(AddressMode::None, |(_, a)| a.mode()));
+        frame_control.set_src_addr_mode(self.src.map_or(AddressMode::None, |(_, a)| a.mode()));
+        let compression = match (self.dst, self.src) {
+            (Some((dst_pan_id, _)), Some((src_pan_id, _))) => dst_pan_id == src_pan_id,
+            _ => false,
+        };
+        frame_control.set_pan_id_compression(compression);
+        frame_control.set_security_enabled(self.security.is_some());
+        frame_control.set_frame_version(if self.security.is_some() {
+            FrameVersion::Ieee2006
+        } else {
+            FrameVersion::Ieee2003
+        });
+
+        let len = 3
+            + self.dst.map_or(0, |(_, address)| 2 + address.len())
+            + self.src.map_or(0, |(_, address)| {
+                if compression {
+                    address.len()
+                } else {
+                    2 + address.len()
+                }
+            })
+            + self.security.map_or(0, |security| security.len())
+            + payload.len();
+        if len > usize::from(Packet::CAPACITY) {
+            return Err(FrameError::TooLong);
+        }
+
+        packet.set_len(len as u8);
+        let mut writer = Writer {
+            data: packet,
+            pos: 0,
+        };
+        writer.bytes(&frame_control.bits().to_le_bytes());
+        writer.u8(self.sequence_number);
+        if let Some((pan_id, address)) = self.dst {
+            writer.bytes(&pan_id.to_le_bytes());
+            writer.address(address);
+        }
+        if let Some((pan_id, address)) = self.src {
+            if !compression {
+                writer.bytes(&pan_id.to_le_bytes());
+            }
+            writer.address(address);
+        }
+        if let Some(security) = &self.security {
+            security.write(&mut writer);
+        }
+        writer.bytes(payload);
+
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], FrameError> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + N)
+            .ok_or(FrameError::TooShort)?;
+        self.pos += N;
+        let mut array = [0; N];
+        array.copy_from_slice(bytes);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> Result<u8, FrameError> {
+        Ok(self.bytes::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, FrameError> {
+        Ok(u16::from_le_bytes(self.bytes()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, FrameError> {
+        Ok(u32::from_le_bytes(self.bytes()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, FrameError> {
+        Ok(u64::from_le_bytes(self.bytes()?))
+    }
+
+    fn address(&mut self, mode: AddressMode) -> Result<Option<Address>, FrameError> {
+        match mode {
+            AddressMode::None => Ok(None),
+            AddressMode::Reserved => Err(FrameError::ReservedAddressMode),
+            AddressMode::Short => Ok(Some(Address::Short(self.u16()?))),
+            AddressMode::Extended => Ok(Some(Address::Extended(self.u64()?))),
+        }
+    }
+}
+
+/// Writes into a slice that is known to be large enough
+struct Writer<'a> {
+    data: &'a mut [u8],
+    pos: usize,
+}
+
+impl Writer<'_> {
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.data[self.pos..self.pos + bytes.len()].copy_from_slice(bytes);
+        self.pos += bytes.len();
+    }
+
+    fn u8(&mut self, byte: u8) {
+        self.bytes(&[byte]);
+    }
+
+    fn address(&mut self, address: Address) {
+        match address {
+            Address::Short(address) => self.bytes(&address.to_le_bytes()),
+            Address::Extended(address) => self.bytes(&address.to_le_bytes()),
+        }
+    }
+}
@@ -10,19 +10,42 @@ use embedded_hal::timer::CountDown as _;
 
 use crate::{
     clocks::{Clocks, ExternalOscillator},
-    pac::{
-        generic::Variant,
-        radio::{state::STATE_A, txpower::TXPOWER_A},
-        RADIO,
-    },
+    fem::{Direction, FrontEnd, NoFem, Trigger},
+    pac::{generic::Variant, radio::state::STATE_A, RADIO},
+    rng::Rng,
     timer::{self, Timer},
 };
 
+mod driver;
+pub mod frame;
+
+#[cfg(feature = "async")]
+pub use self::driver::on_interrupt;
+pub use self::driver::{Driver, DriverEvent, RxFrame};
+pub use crate::radio::TxPower;
+
+use self::frame::{Address, Frame, FrameBuilder, FrameType};
+
 /// IEEE 802.15.4 radio
-pub struct Radio<'c> {
+///
+/// `F` is the front-end module whose amplifiers are switched on whenever the RADIO ramps up,
+/// see `set_fem`.
+pub struct Radio<'c, F = NoFem> {
     radio: RADIO,
     // RADIO needs to be (re-)enabled to pick up new settings
     needs_enable: bool,
+    // frames not addressed to us are dropped when set
+    filter: Option<AddressFilter>,
+    // acknowledge received frames that request it
+    auto_ack: bool,
+    // decides the frame pending bit of outgoing ACKs
+    frame_pending: Option<fn(&Packet) -> bool>,
+    // outgoing ACK frame, kept here so it lives in RAM
+    ack: Packet,
+    // switched on whenever the RADIO ramps up
+    fem: F,
+    // requested TX power at the antenna, in dBm
+    output_power: i8,
     // used to freeze `Clocks`
     _clocks: PhantomData<&'c ()>,
 }
@@ -39,6 +62,62 @@ pub const DEFAULT_TXPOWER: TxPower = TxPower::_0dBm;
 /// Default Start of Frame Delimiter = `0xA7` (IEEE compliant)
 pub const DEFAULT_SFD: u8 = 0xA7;
 
+/// Maximum number of retransmissions when no ACK is received (macMaxFrameRetries default)
+pub const DEFAULT_MAX_FRAME_RETRIES: u8 = 3;
+
+/// Broadcast PAN ID and short address
+pub const BROADCAST: u16 = 0xFFFF;
+
+/// Time between the end of a frame and the start of its ACK (aTurnaroundTime: 12 symbols)
+const TURNAROUND_TIME_US: u32 = 192;
+
+/// How long to wait for an ACK after the end of a frame (macAckWaitDuration: 54 symbols)
+const ACK_WAIT_DURATION_US: u32 = 864;
+
+/// RADIO ramp-up time in IEEE 802.15.4 mode (tTXEN and tRXEN)
+const RAMP_UP_US: u32 = 130;
+
+/// Duration of a CSMA-CA backoff period (aUnitBackoffPeriod: 20 symbols)
+pub const UNIT_BACKOFF_PERIOD_US: u32 = 320;
+
+/// Addresses of this device, used to drop frames addressed to other devices
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AddressFilter {
+    /// PAN ID (macPANId)
+    pub pan_id: u16,
+    /// Short address (macShortAddress)
+    pub short_address: u16,
+    /// Extended address (aExtendedAddress)
+    pub extended_address: u64,
+    /// Accept data and MAC command frames without a destination address that come from our PAN,
+    /// as a PAN coordinator does
+    pub pan_coordinator: bool,
+}
+
+/// Parameters of the unslotted CSMA-CA algorithm, see `Radio::send_csma_ca`
+///
+/// The `Default` values are the ones of the standard.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CsmaConfig {
+    /// Initial backoff exponent (macMinBE), at most `max_be`
+    pub min_be: u8,
+    /// Maximum backoff exponent (macMaxBE), in the range `3..=8`
+    pub max_be: u8,
+    /// Number of times the backoff is repeated after finding the channel busy before giving up
+    /// (macMaxCSMABackoffs), at most 5
+    pub max_backoffs: u8,
+}
+
+impl Default for CsmaConfig {
+    fn default() -> Self {
+        Self {
+            min_be: 3,
+            max_be: 5,
+            max_backoffs: 4,
+        }
+    }
+}
+
 // TODO expose the other variants in `pac::CCAMODE_A`
 /// Clear Channel Assessment method
 pub enum Cca {
@@ -93,67 +172,18 @@ pub enum Channel {
     _26 = 80,
 }
 
-/// Transmission power in dBm (decibel milliwatt)
-// TXPOWERA enum minus the deprecated Neg30dBm variant and with better docs
-#[derive(Clone, Copy, PartialEq)]
-pub enum TxPower {
-    /// +8 dBm
-    Pos8dBm,
-    /// +7 dBm
-    Pos7dBm,
-    /// +6 dBm (~4 mW)
-    Pos6dBm,
-    /// +5 dBm
-    Pos5dBm,
-    /// +4 dBm
-    Pos4dBm,
-    /// +3 dBm (~2 mW)
-    Pos3dBm,
-    /// +2 dBm
-    Pos2dBm,
-    /// 0 dBm (1 mW)
-    _0dBm,
-    /// -4 dBm
-    Neg4dBm,
-    /// -8 dBm
-    Neg8dBm,
-    /// -12 dBm
-    Neg12dBm,
-    /// -16 dBm
-    Neg16dBm,
-    /// -20 dBm (10 μW)
-    Neg20dBm,
-    /// -40 dBm (0.1 μW)
-    Neg40dBm,
-}
-
-impl TxPower {
-    fn _into(self) -> TXPOWER_A {
-        match self {
-            TxPower::Neg40dBm => TXPOWER_A::NEG40DBM,
-            TxPower::Neg20dBm => TXPOWER_A::NEG20DBM,
-            TxPower::Neg16dBm => TXPOWER_A::NEG16DBM,
-            TxPower::Neg12dBm => TXPOWER_A::NEG12DBM,
-            TxPower::Neg8dBm => TXPOWER_A::NEG8DBM,
-            TxPower::Neg4dBm => TXPOWER_A::NEG4DBM,
-            TxPower::_0dBm => TXPOWER_A::_0DBM,
-            TxPower::Pos2dBm => TXPOWER_A::POS2DBM,
-            TxPower::Pos3dBm => TXPOWER_A::POS3DBM,
-            TxPower::Pos4dBm => TXPOWER_A::POS4DBM,
-            TxPower::Pos5dBm => TXPOWER_A::POS5DBM,
-            TxPower::Pos6dBm => TXPOWER_A::POS6DBM,
-            TxPower::Pos7dBm => TXPOWER_A::POS7DBM,
-            TxPower::Pos8dBm => TXPOWER_A::POS8DBM,
-        }
-    }
-}
-
 impl<'c> Radio<'c> {
     /// Initializes the radio for IEEE 802.15.4 operation
     pub fn init<L, LSTAT>(radio: RADIO, _clocks: &'c Clocks<ExternalOscillator, L, LSTAT>) -> Self {
         let mut radio = Self {
             needs_enable: false,
             radio,
+            filter: None,
+            auto_ack: false,
+            frame_pending: None,
+            ack: Packet::new(),
+            fem: NoFem,
+            output_power: DEFAULT_TXPOWER.dbm(),
             _clocks: PhantomData,
         };
 
@@ -220,7 +250,12 @@ impl<'c> Radio<'c> {
 
         radio
     }
+}
 
+impl<'c, F> Radio<'c, F>
+where
+    F: FrontEnd,
+{
     /// Changes the radio channel
     pub fn set_channel(&mut self, channel: Channel) {
         self.needs_enable = true;
@@ -253,11 +288,82 @@ impl<'c> Radio<'c> {
     }
 
     /// Changes the TX power
+    ///
+    /// With a front-end module, this is the power at the antenna, see `set_output_power`.
     pub fn set_txpower(&mut self, power: TxPower) {
+        self.set_output_power(power.dbm());
+    }
+
+    /// Changes the TX power at the antenna, in dBm, compensating for the gain of the front-end
+    /// module (if any)
+    ///
+    /// The RADIO is set to the highest power that does not exceed `dbm` after amplification, or
+    /// to its lowest power. Returns the resulting power at the antenna.
+    pub fn set_output_power(&mut self, dbm: i8) -> i8 {
+        self.output_power = dbm;
+        let gain = self.fem.pa_gain();
+        let power = TxPower::at_most(dbm.saturating_sub(gain));
+
         self.needs_enable = true;
+        // NOTE(unsafe) every `TxPower` is a valid TXPOWER value
         self.radio
             .txpower
-            .write(|w| w.txpower().variant(power._into()));
+            .write(|w| unsafe { w.bits(power.bits()) });
+        power.dbm().saturating_add(gain)
+    }
+
+    /// Sets the front-end module whose amplifiers are switched on whenever the RADIO ramps up,
+    /// and switched off when it is disabled, returning the radio and the previous front-end
+    /// module
+    ///
+    /// The TX power is compensated for the gain of the front-end module, see
+    /// `set_output_power`. Pass `NoFem` to remove the front-end module; a `&mut` reference to
+    /// a front-end module can be passed as well.
+    pub fn set_fem<G>(mut self, fem: G) -> (Radio<'c, G>, F)
+    where
+        G: FrontEnd,
+    {
+        self.disable();
+        let mut radio = Radio {
+            radio: self.radio,
+            needs_enable: self.needs_enable,
+            filter: self.filter,
+            auto_ack: self.auto_ack,
+            frame_pending: self.frame_pending,
+            ack: self.ack,
+            fem,
+            output_power: self.output_power,
+            _clocks: PhantomData,
+        };
+        radio.set_output_power(radio.output_power);
+        (radio, self.fem)
+    }
+
+    /// Sets the addresses of this device
+    ///
+    /// With a filter set, `recv` and `recv_timeout` drop frames with a valid CRC that are not
+    /// addressed to this device (or broadcast), and continue listening. Pass `None` to receive
+    /// all frames.
+    pub fn set_address_filter(&mut self, filter: Option<AddressFilter>) {
+        self.filter = filter;
+    }
+
+    /// Enables or disables the automatic acknowledgement of received frames
+    ///
+    /// When enabled, `recv` and `recv_timeout` send an ACK for every received frame that passes
+    /// the address filter and has the AR (acknowledgement request) bit set. The ACK is sent
+    /// `aTurnaroundTime` after the frame, timed by the RADIO's TIFS and `DISABLED_TXEN` shortcut.
+    pub fn set_auto_ack(&mut self, enabled: bool) {
+        self.auto_ack = enabled;
+    }
+
+    /// Sets the function that decides whether the frame pending bit is set in the ACK sent for
+    /// a received frame, e.g. because data is buffered for the device polling us
+    ///
+    /// Without a function, the frame pending bit is never set. The function is called between
+    /// the reception of the frame and the transmission of the ACK, so it must return quickly.
+    pub fn set_frame_pending_callback(&mut self, callback: Option<fn(&Packet) -> bool>) {
+        self.frame_pending = callback;
     }
 
     /// Sample the received signal power (i.e. the presence of possibly interfering signals)
@@ -307,22 +413,23 @@ impl<'c> Radio<'c> {
     /// This methods returns the `Ok` variant if the CRC included the packet was successfully
     /// validated by the hardware; otherwise it returns the `Err` variant. In either case, `packet`
     /// will be updated with the received packet's data
+    ///
+    /// Frames dropped by the address filter are not returned, see `set_address_filter`.
     pub fn recv(&mut self, packet: &mut Packet) -> Result<u16, u16> {
-        // Start the read
-        // NOTE(unsafe) We block until reception completes or errors
-        unsafe {
-            self.start_recv(packet);
-        }
+        loop {
+            // Start the read
+            // NOTE(unsafe) We block until reception completes or errors
+            unsafe {
+                self.start_recv(packet, self.auto_ack);
+            }
 
-        // wait until we have received something
-        self.wait_for_event(Event::End);
-        dma_end_fence();
+            // wait until we have received something
+            self.wait_for_event(Event::End);
+            dma_end_fence();
 
-        let crc = self.radio.rxcrc.read().rxcrc().bits() as u16;
-        if self.radio.crcstatus.read().crcstatus().bit_is_set() {
-            Ok(crc)
-        } else {
-            Err(crc)
+            if let Some(result) = self.finish_recv(packet, true) {
+                return result;
+            }
         }
     }
 
@@ -338,56 +445,72 @@ impl<'c> Radio<'c> {
     /// Note that the time it takes to switch the radio to RX mode is included in the timeout count.
     /// This transition may take up to a hundred of microseconds; see the section 6.20.15.8 in the
     /// Product Specification for more details about timing
+    ///
+    /// Frames dropped by the address filter are not returned, see `set_address_filter`.
     pub fn recv_timeout<I>(
         &mut self,
         packet: &mut Packet,
         timer: &mut Timer<I>,
         microseconds: u32,
     ) -> Result<u16, Error>
+    where
+        I: timer::Instance,
+    {
+        self.recv_timeout_inner(packet, timer, microseconds, true)
+    }
+
+    /// Receives a frame like `recv_timeout`; `mac` enables address filtering and automatic ACKs
+    fn recv_timeout_inner<I>(
+        &mut self,
+        packet: &mut Packet,
+        timer: &mut Timer<I>,
+        microseconds: u32,
+        mac: bool,
+    ) -> Result<u16, Error>
     where
         I: timer::Instance,
     {
         // Start the timeout timer
         timer.start(microseconds);
 
-        // Start the read
-        // NOTE(unsafe) We block until reception completes or errors
-        unsafe {
-            self.start_recv(packet);
-        }
+        loop {
+            // Start the read
+            // NOTE(unsafe) We block until reception completes or errors
+            unsafe {
+                self.start_recv(packet, mac && self.auto_ack);
+            }
 
-        // Wait for transmission to end
-        let mut recv_completed = false;
+            // Wait for transmission to end
+            let mut recv_completed = false;
 
-        loop {
-            if self.radio.events_end.read().bits() != 0 {
-                // transfer complete
-                dma_end_fence();
-                recv_completed = true;
-                break;
+            loop {
+                if self.radio.events_end.read().bits() != 0 {
+                    // transfer complete
+                    self.radio.events_end.reset();
+                    dma_end_fence();
+                    recv_completed = true;
+                    break;
+                }
+
+                if timer.wait().is_ok() {
+                    // timeout
+                    break;
+                }
             }
 
-            if timer.wait().is_ok() {
-                // timeout
-                break;
+            if !recv_completed {
+                // Cancel the reception if it did not complete until now
+                self.cancel_recv();
+                return Err(Error::Timeout);
             }
-        }
 
-        if !recv_completed {
-            // Cancel the reception if it did not complete until now
-            self.cancel_recv();
-            Err(Error::Timeout)
-        } else {
-            let crc = self.radio.rxcrc.read().rxcrc().bits() as u16;
-            if self.radio.crcstatus.read().crcstatus().bit_is_set() {
-                Ok(crc)
-            } else {
-                Err(Error::Crc(crc))
+            if let Some(result) = self.finish_recv(packet, mac) {
+                return result.map_err(Error::Crc);
             }
         }
     }
 
-    unsafe fn start_recv(&mut self, packet: &mut Packet) {
+    unsafe fn start_recv(&mut self, packet: &mut Packet, auto_ack: bool) {
         // NOTE we do NOT check the address of `packet` because the mutable reference ensures it's
         // allocated in RAM
 
@@ -397,6 +520,19 @@ impl<'c> Radio<'c> {
 
         self.put_in_rx_mode();
 
+        if auto_ack {
+            // Once the frame has been received, the radio turns around to TX so an ACK can be
+            // sent `aTurnaroundTime` after the end of the frame; `finish_recv` either sends the
+            // ACK or cancels the transmission
+            self.radio.events_ready.reset();
+            self.radio.events_disabled.reset();
+            self.radio.tifs.write(|w| w.bits(TURNAROUND_TIME_US));
+            self.radio
+                .shorts
+                .write(|w| w.end_disable().set_bit().disabled_txen().set_bit());
+            self.enable_fem(Direction::Tx, TURNAROUND_TIME_US, Trigger::Disabled);
+        }
+
         // NOTE(unsafe) DMA transfer has not yet started
         // set up RX buffer
         self.radio
@@ -409,12 +545,115 @@ impl<'c> Radio<'c> {
     }
 
     fn cancel_recv(&mut self) {
+        self.radio.shorts.reset();
         self.radio.tasks_stop.write(|w| w.tasks_stop().set_bit());
         self.wait_for_state_a(STATE_A::RXIDLE);
         // DMA transfer may have been in progress so synchronize with its memory operations
         dma_end_fence();
     }
 
+    /// Handles a received frame: sends or cancels the ACK if the turnaround to TX has been armed
+    /// and applies the address filter
+    ///
+    /// Returns `None` if the frame has been dropped by the address filter.
+    fn finish_recv(&mut self, packet: &Packet, mac: bool) -> Option<Result<u16, u16>> {
+        let crc = self.radio.rxcrc.read().rxcrc().bits() as u16;
+        let crc_ok = self.radio.crcstatus.read().crcstatus().bit_is_set();
+        let accepted = crc_ok && (!mac || self.accepts(packet));
+
+        if mac && self.auto_ack {
+            let ack = if accepted {
+                ack_requested(packet)
+            } else {
+                None
+            };
+            self.finish_turnaround(packet, ack);
+        }
+
+        if !crc_ok {
+            Some(Err(crc))
+        } else if accepted {
+            Some(Ok(crc))
+        } else {
+            None
+        }
+    }
+
+    /// Sends an ACK with the `ack` sequence number for `packet`, or cancels the armed turnaround
+    /// to TX if `ack` is `None`
+    fn finish_turnaround(&mut self, packet: &Packet, ack: Option<u8>) {
+        // the END_DISABLE shortcut always fires, so wait for it to complete before clearing the
+        // shortcuts; by then the DISABLED_TXEN shortcut has enabled the transmitter
+        while self.radio.events_disabled.read().bits() == 0 {}
+        self.radio.events_disabled.reset();
+
+        // no further turnarounds
+        self.radio.shorts.reset();
+
+        let sequence_number = match ack {
+            Some(sequence_number) => sequence_number,
+            None => {
+                self.disable();
+                return;
+            }
+        };
+
+        let pending = self.frame_pending.map_or(false, |f| f(packet));
+        write_ack(&mut self.ack, sequence_number, pending);
+
+        // NOTE(unsafe) DMA transfer has not yet started
+        unsafe {
+            self.radio
+                .packetptr
+                .write(|w| w.packetptr().bits(self.ack.buffer.as_ptr() as u32));
+        }
+
+        // the transmitter has been enabled by the DISABLED_TXEN shortcut and TIFS delays its
+        // ramp-up so that the ACK starts `aTurnaroundTime` after the received frame
+        while self.radio.events_ready.read().events_ready().bit_is_clear() {}
+        self.radio.events_ready.reset();
+        self.radio.events_phyend.reset();
+
+        dma_start_fence();
+        self.radio.tasks_start.write(|w| w.tasks_start().set_bit());
+        self.wait_for_event(Event::PhyEnd);
+    }
+
+    /// Returns `true` if `packet` is addressed to this device according to the address filter
+    fn accepts(&self, packet: &Packet) -> bool {
+        let filter = match &self.filter {
+            Some(filter) => filter,
+            None => return true,
+        };
+
+        let frame = match Frame::parse(packet) {
+            Ok(frame) => frame,
+            Err(_) => return false,
+        };
+        // an elided destination PAN ID (IEEE 802.15.4-2015) is the one of our PAN
+        let pan_matches = frame
+            .dst_pan_id()
+            .map_or(true, |pan| pan == BROADCAST || pan == filter.pan_id);
+
+        match frame.dst_address() {
+            // no destination address: only beacons and, for a PAN coordinator, frames from our
+            // PAN are accepted
+            None => match frame.frame_type() {
+                FrameType::Beacon => {
+                    filter.pan_id == BROADCAST || frame.src_pan_id() == Some(filter.pan_id)
+                }
+                FrameType::Data | FrameType::MacCommand => {
+                    filter.pan_coordinator && frame.src_pan_id() == Some(filter.pan_id)
+                }
+                _ => false,
+            },
+            Some(Address::Short(address)) => {
+                pan_matches && (address == BROADCAST || address == filter.short_address)
+            }
+            Some(Address::Extended(address)) => pan_matches && address == filter.extended_address,
+        }
+    }
+
     /// Tries to send the given `packet`
     ///
     /// This method performs Clear Channel Assessment (CCA) first and sends the `packet` only if the
@@ -444,6 +683,8 @@ impl<'c> Radio<'c> {
         self.radio
             .shorts
             .modify(|_, w| w.ccaidle_txen().set_bit().txready_start().set_bit());
+        // the transmitter ramps up from RX without a DISABLED event in between
+        self.enable_fem(Direction::Tx, RAMP_UP_US, Trigger::CcaIdle);
 
         // the DMA transfer will start at some point after the following write operation so
         // we place the compiler fence here
@@ -481,7 +722,7 @@ impl<'c> Radio<'c> {
     ///
     /// This is utility method that *consecutively* calls the `try_send` method until it succeeds.
     /// Note that this approach is *not* IEEE spec compliant -- there must be delay between failed
-    /// CCA attempts to be spec compliant
+    /// CCA attempts to be spec compliant. Use `send_csma_ca` for that
     ///
     /// NOTE this method will *not* modify the `packet` argument. The mutable reference is used to
     /// ensure the `packet` buffer is allocated in RAM, which is required by the RADIO peripheral
@@ -499,6 +740,8 @@ impl<'c> Radio<'c> {
         self.radio
             .shorts
             .modify(|_, w| w.ccaidle_txen().set_bit().txready_start().set_bit());
+        // the transmitter ramps up from RX without a DISABLED event in between
+        self.enable_fem(Direction::Tx, RAMP_UP_US, Trigger::CcaIdle);
 
         // the DMA transfer will start at some point after the following write operation so
         // we place the compiler fence here
@@ -541,6 +784,102 @@ impl<'c> Radio<'c> {
         self.radio.shorts.reset();
     }
 
+    /// Sends the given `packet` using the unslotted CSMA-CA algorithm
+    ///
+    /// Before each Clear Channel Assessment, the radio waits for a random number of backoff
+    /// periods (`UNIT_BACKOFF_PERIOD_US`), drawn from `rng` in the range `0..2^BE` and timed with
+    /// `timer`. The backoff exponent BE starts at `config.min_be` and is incremented, up to
+    /// `config.max_be`, every time the channel is busy. If the channel is still busy after
+    /// `config.max_backoffs` retries, the `ChannelAccessFailure` error is returned and the
+    /// `packet` is not sent.
+    ///
+    /// NOTE this method will *not* modify the `packet` argument. The mutable reference is used to
+    /// ensure the `packet` buffer is allocated in RAM, which is required by the RADIO peripheral
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `config.max_be` is larger than 8 or `config.min_be` is larger than
+    /// `config.max_be`
+    pub fn send_csma_ca<I>(
+        &mut self,
+        packet: &mut Packet,
+        rng: &mut Rng,
+        timer: &mut Timer<I>,
+        config: &CsmaConfig,
+    ) -> Result<(), Error>
+    where
+        I: timer::Instance,
+    {
+        assert!(config.max_be <= 8 && config.min_be <= config.max_be);
+
+        let mut be = config.min_be;
+        for _ in 0..=config.max_backoffs {
+            let periods = u32::from(rng.random_u16()) & ((1 << be) - 1);
+            // a zero-length delay would never end
+            if periods != 0 {
+                timer.delay(periods * UNIT_BACKOFF_PERIOD_US);
+            }
+
+            if self.try_send(packet).is_ok() {
+                return Ok(());
+            }
+
+            be = (be + 1).min(config.max_be);
+        }
+
+        Err(Error::ChannelAccessFailure)
+    }
+
+    /// Sends the given `packet` and, if its AR (acknowledgement request) bit is set, waits for
+    /// the ACK, retransmitting it up to `max_retries` times
+    ///
+    /// The `packet` is sent using `send`. Returns the frame pending bit of the ACK, or `false` if
+    /// no ACK was requested. If no ACK is received after the last retransmission, the `NoAck`
+    /// error is returned.
+    ///
+    /// NOTE this method will *not* modify the `packet` argument. The mutable reference is used to
+    /// ensure the `packet` buffer is allocated in RAM, which is required by the RADIO peripheral
+    pub fn send_with_ack<I>(
+        &mut self,
+        packet: &mut Packet,
+        timer: &mut Timer<I>,
+        max_retries: u8,
+    ) -> Result<bool, Error>
+    where
+        I: timer::Instance,
+    {
+        let sequence_number = match ack_requested(packet) {
+            Some(sequence_number) => sequence_number,
+            None => {
+                self.send(packet);
+                return Ok(false);
+            }
+        };
+
+        let mut ack = Packet::new();
+        for _ in 0..=max_retries {
+            self.send(packet);
+
+            // the ACK window includes the time it takes to turn the radio around
+            if self
+                .recv_timeout_inner(&mut ack, timer, ACK_WAIT_DURATION_US, false)
+                .is_ok()
+            {
+                match Frame::parse(&ack) {
+                    Ok(frame)
+                        if frame.frame_type() == FrameType::Ack
+                            && frame.sequence_number() == Some(sequence_number) =>
+                    {
+                        return Ok(frame.frame_control().frame_pending());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Err(Error::NoAck)
+    }
+
     /// Sends the specified `packet` without first performing CCA
     ///
     /// Acknowledgment packets must be sent using this method
@@ -572,6 +911,8 @@ impl<'c> Radio<'c> {
 
     /// Moves the radio from any state to the DISABLED state
     fn disable(&mut self) {
+        self.disable_fem();
+
         // See figure 110 in nRF52840-PS
         loop {
             if let Variant::Val(state) = self.radio.state.read().state().variant() {
@@ -633,6 +974,7 @@ impl<'c> Radio<'c> {
 
         if enable {
             self.needs_enable = false;
+            self.enable_fem(Direction::Rx, RAMP_UP_US, Trigger::Now);
             self.radio.tasks_rxen.write(|w| w.tasks_rxen().set_bit());
             self.wait_for_state_a(STATE_A::RXIDLE);
         }
@@ -644,11 +986,22 @@ impl<'c> Radio<'c> {
 
         if state != State::TxIdle || self.needs_enable {
             self.needs_enable = false;
+            self.enable_fem(Direction::Tx, RAMP_UP_US, Trigger::Now);
             self.radio.tasks_txen.write(|w| w.tasks_txen().set_bit());
             self.wait_for_state_a(STATE_A::TXIDLE);
         }
     }
 
+    /// Lets the front-end module (if any) switch its amplifier on before the RADIO is ready
+    fn enable_fem(&mut self, direction: Direction, start_us: u32, trigger: Trigger) {
+        self.fem.enable(&self.radio, direction, start_us, trigger);
+    }
+
+    /// Switches the amplifiers of the front-end module (if any) off
+    fn disable_fem(&mut self) {
+        self.fem.disable();
+    }
+
     fn state(&self) -> State {
         if let Variant::Val(state) = self.radio.state.read().state().variant() {
             match state {
@@ -689,11 +1042,18 @@ impl<'c> Radio<'c> {
 
 /// Error
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum Error {
     /// Incorrect CRC
     Crc(u16),
     /// Timeout
     Timeout,
+    /// No acknowledgement was received, even after retransmissions
+    NoAck,
+    /// The transmit queue of the `Driver` is full
+    QueueFull,
+    /// The channel was busy at every Clear Channel Assessment of the CSMA-CA algorithm
+    ChannelAccessFailure,
 }
 
 /// Driver state
@@ -707,6 +1067,24 @@ enum State {
     TxIdle,
 }
 
+/// Returns the sequence number to acknowledge if `packet` requests an ACK
+fn ack_requested(packet: &Packet) -> Option<u8> {
+    let frame = Frame::parse(packet).ok()?;
+    if frame.frame_control().ack_request() {
+        frame.sequence_number()
+    } else {
+        None
+    }
+}
+
+/// Writes the ACK of the frame with the given sequence number into `ack`
+fn write_ack(ack: &mut Packet, sequence_number: u8, frame_pending: bool) {
+    // an ACK has no addresses and no payload so it always fits
+    let _ = FrameBuilder::ack(sequence_number)
+        .frame_pending(frame_pending)
+        .build(ack, &[]);
+}
+
 /// NOTE must be followed by a volatile write operation
 fn dma_start_fence() {
     atomic::compiler_fence(Ordering::Release);
@@ -733,6 +1111,7 @@ enum Event {
 /// `copy_from_slice` methods. These methods will automatically update the PHR.
 ///
 /// See figure 119 in the Product Specification of the nRF52840 for more details
+#[derive(Clone)]
 pub struct Packet {
     buffer: [u8; Self::SIZE],
 }
@@ -29,6 +29,10 @@ pub use nrf9160_pac as pac;
 
 #[cfg(feature = "51")]
 pub mod adc;
+#[cfg(all(feature = "async", not(feature = "51")))]
+mod asynch;
+#[cfg(not(any(feature = "51", feature = "9160")))]
+pub mod ble;
 #[cfg(not(feature = "9160"))]
 pub mod ccm;
 pub mod clocks;
@@ -36,8 +40,14 @@ pub mod clocks;
 pub mod comp;
 #[cfg(not(feature = "51"))]
 pub mod delay;
+#[cfg(any(feature = "52811", feature = "52833"))]
+pub mod dfe;
 #[cfg(not(feature = "9160"))]
 pub mod ecb;
+#[cfg(not(any(feature = "51", feature = "9160")))]
+pub mod esb;
+#[cfg(not(any(feature = "51", feature = "9160")))]
+pub mod fem;
 pub mod gpio;
 #[cfg(not(feature = "9160"))]
 pub mod gpiote;
@@ -49,12 +59,23 @@ pub mod ieee802154;
 pub mod lpcomp;
 #[cfg(not(feature = "51"))]
 pub mod nvmc;
+#[cfg(any(
+    feature = "52832",
+    feature = "52833",
+    feature = "52840",
+    feature = "9160"
+))]
+pub mod pdm;
 #[cfg(not(feature = "9160"))]
 pub mod ppi;
+#[cfg(not(any(feature = "51", feature = "9160")))]
+pub mod power;
 #[cfg(not(feature = "51"))]
 pub mod pwm;
 #[cfg(not(any(feature = "51", feature = "9160")))]
 pub mod qdec;
+#[cfg(not(any(feature = "51", feature = "9160")))]
+pub mod radio;
 #[cfg(not(feature = "9160"))]
 pub mod rng;
 pub mod rtc;
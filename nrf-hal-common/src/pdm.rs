@@ -0,0 +1,418 @@
+//! HAL interface for the PDM peripheral.
+//!
+//! The PDM (Pulse Density Modulation) interface reads the output of digital
+//! microphones and stores the resulting 16-bit PCM samples in RAM via EasyDMA.
+
+#[cfg(not(feature = "9160"))]
+use crate::pac::{pdm, PDM as PDM_PAC};
+#[cfg(feature = "9160")]
+use crate::pac::{pdm_ns as pdm, PDM_NS as PDM_PAC};
+use crate::{
+    gpio::{Floating, Input, Output, Pin, PushPull},
+    pac::generic::Reg,
+};
+use core::sync::atomic::{compiler_fence, Ordering};
+use embedded_dma::*;
+
+use pdm::{_EVENTS_END, _EVENTS_STARTED, _EVENTS_STOPPED, _TASKS_START, _TASKS_STOP};
+
+pub struct Pdm {
+    pdm: PDM_PAC,
+}
+
+// PDM EasyDMA MAXCNT bit length = 15
+const MAX_DMA_MAXCNT: usize = (1 << 15) - 1;
+
+impl Pdm {
+    /// Takes ownership of the raw PDM peripheral, returning a safe wrapper.
+    ///
+    /// The interface is configured for a mono microphone sampled on the
+    /// falling edge of the default 1.032 MHz clock, with 0 dB gain.
+    pub fn new(
+        pdm: PDM_PAC,
+        clk_pin: &Pin<Output<PushPull>>,
+        din_pin: &Pin<Input<Floating>>,
+    ) -> Self {
+        pdm.psel.clk.write(|w| {
+            unsafe { w.bits(clk_pin.psel_bits()) };
+            w.connect().connected()
+        });
+        pdm.psel.din.write(|w| {
+            unsafe { w.bits(din_pin.psel_bits()) };
+            w.connect().connected()
+        });
+
+        let pdm = Self { pdm };
+        pdm.set_frequency(Frequency::_1032K)
+            .set_operation(Operation::Mono)
+            .set_edge(Edge::LeftFalling)
+            .set_gain(0, 0);
+        pdm
+    }
+
+    /// Enables the PDM module.
+    #[inline(always)]
+    pub fn enable(&self) -> &Self {
+        self.pdm.enable.write(|w| w.enable().enabled());
+        self
+    }
+
+    /// Disables the PDM module.
+    #[inline(always)]
+    pub fn disable(&self) -> &Self {
+        self.pdm.enable.write(|w| w.enable().disabled());
+        self
+    }
+
+    /// Starts continuous PDM sampling.
+    #[inline(always)]
+    pub fn start(&self) -> &Self {
+        self.enable();
+        self.pdm.tasks_start.write(|w| unsafe { w.bits(1) });
+        self
+    }
+
+    /// Stops PDM sampling and waits until it has stopped.
+    #[inline(always)]
+    pub fn stop(&self) -> &Self {
+        compiler_fence(Ordering::SeqCst);
+        self.pdm.tasks_stop.write(|w| unsafe { w.bits(1) });
+        while self.pdm.events_stopped.read().bits() == 0 {}
+        self
+    }
+
+    /// Sets the PDM clock frequency.
+    #[inline(always)]
+    pub fn set_frequency(&self, freq: Frequency) -> &Self {
+        self.pdm
+            .pdmclkctrl
+            .write(|w| unsafe { w.bits(freq.into()) });
+        self
+    }
+
+    /// Sets mono or stereo operation.
+    #[inline(always)]
+    pub fn set_operation(&self, operation: Operation) -> &Self {
+        self.pdm
+            .mode
+            .modify(|_r, w| w.operation().bit(operation.into()));
+        self
+    }
+
+    /// Sets the clock edge on which the left (or mono) channel is sampled.
+    #[inline(always)]
+    pub fn set_edge(&self, edge: Edge) -> &Self {
+        self.pdm.mode.modify(|_r, w| w.edge().bit(edge.into()));
+        self
+    }
+
+    /// Sets the left and right channel gain in 0.5 dB steps.
+    ///
+    /// Valid values range from -40 (-20 dB) to 40 (+20 dB).
+    #[inline(always)]
+    pub fn set_gain(&self, left: i8, right: i8) -> &Self {
+        assert!((-40..=40).contains(&left) && (-40..=40).contains(&right));
+        // A register value of 0x28 is 0 dB.
+        self.pdm
+            .gainl
+            .write(|w| unsafe { w.gainl().bits((0x28 + left) as u8) });
+        self.pdm
+            .gainr
+            .write(|w| unsafe { w.gainr().bits((0x28 + right) as u8) });
+        self
+    }
+
+    /// Continuously receives samples into two alternating buffers.
+    ///
+    /// Both buffers must have the same length. In stereo operation, samples
+    /// alternate between the left and right channel, so the length should be
+    /// even. Returns a value that represents the in-progress DMA stream, see
+    /// [`Stream::process`].
+    #[allow(unused_mut)]
+    pub fn stream<B>(self, mut buffers: [B; 2]) -> Result<Stream<B>, Error>
+    where
+        B: WriteBuffer<Word = i16> + 'static,
+    {
+        let (ptr, len) = unsafe { buffers[0].write_buffer() };
+        let (_, next_len) = unsafe { buffers[1].write_buffer() };
+        if len != next_len {
+            return Err(Error::BuffersDontMatch);
+        }
+        if len > MAX_DMA_MAXCNT {
+            return Err(Error::BufferTooLong);
+        }
+
+        self.pdm
+            .sample
+            .ptr
+            .write(|w| unsafe { w.sampleptr().bits(ptr as u32) });
+        self.pdm
+            .sample
+            .maxcnt
+            .write(|w| unsafe { w.buffsize().bits(len as _) });
+
+        self.reset_event(PdmEvent::Started);
+        self.reset_event(PdmEvent::Stopped);
+        self.reset_event(PdmEvent::End);
+
+        // Conservative compiler fence to prevent starting the transfer before
+        // the pointer and maxcount have been set.
+        compiler_fence(Ordering::SeqCst);
+
+        self.start();
+
+        Ok(Stream {
+            inner: Some(StreamInner {
+                buffers,
+                pdm: self,
+                next: 0,
+                filling: None,
+            }),
+        })
+    }
+
+    /// Checks if an event has been triggered.
+    #[inline(always)]
+    pub fn is_event_triggered(&self, event: PdmEvent) -> bool {
+        match event {
+            PdmEvent::Started => self.pdm.events_started.read().bits() != 0,
+            PdmEvent::Stopped => self.pdm.events_stopped.read().bits() != 0,
+            PdmEvent::End => self.pdm.events_end.read().bits() != 0,
+        }
+    }
+
+    /// Marks event as handled.
+    #[inline(always)]
+    pub fn reset_event(&self, event: PdmEvent) {
+        match event {
+            PdmEvent::Started => self.pdm.events_started.reset(),
+            PdmEvent::Stopped => self.pdm.events_stopped.reset(),
+            PdmEvent::End => self.pdm.events_end.reset(),
+        }
+    }
+
+    /// Enables interrupt triggering on the specified event.
+    #[inline(always)]
+    pub fn enable_interrupt(&self, event: PdmEvent) -> &Self {
+        match event {
+            PdmEvent::Started => self.pdm.intenset.modify(|_r, w| w.started().set()),
+            PdmEvent::Stopped => self.pdm.intenset.modify(|_r, w| w.stopped().set()),
+            PdmEvent::End => self.pdm.intenset.modify(|_r, w| w.end().set()),
+        };
+        self
+    }
+
+    /// Disables interrupt triggering on the specified event.
+    #[inline(always)]
+    pub fn disable_interrupt(&self, event: PdmEvent) -> &Self {
+        match event {
+            PdmEvent::Started => self.pdm.intenclr.modify(|_r, w| w.started().clear()),
+            PdmEvent::Stopped => self.pdm.intenclr.modify(|_r, w| w.stopped().clear()),
+            PdmEvent::End => self.pdm.intenclr.modify(|_r, w| w.end().clear()),
+        };
+        self
+    }
+
+    /// Returns reference to `Started` event endpoint for PPI.
+    #[inline(always)]
+    pub fn event_started(&self) -> &Reg<u32, _EVENTS_STARTED> {
+        &self.pdm.events_started
+    }
+
+    /// Returns reference to `Stopped` event endpoint for PPI.
+    #[inline(always)]
+    pub fn event_stopped(&self) -> &Reg<u32, _EVENTS_STOPPED> {
+        &self.pdm.events_stopped
+    }
+
+    /// Returns reference to `End` event endpoint for PPI.
+    #[inline(always)]
+    pub fn event_end(&self) -> &Reg<u32, _EVENTS_END> {
+        &self.pdm.events_end
+    }
+
+    /// Returns reference to `Start` task endpoint for PPI.
+    #[inline(always)]
+    pub fn task_start(&self) -> &Reg<u32, _TASKS_START> {
+        &self.pdm.tasks_start
+    }
+
+    /// Returns reference to `Stop` task endpoint for PPI.
+    #[inline(always)]
+    pub fn task_stop(&self) -> &Reg<u32, _TASKS_STOP> {
+        &self.pdm.tasks_stop
+    }
+
+    /// Consumes `self` and returns back the raw peripheral.
+    pub fn free(self) -> PDM_PAC {
+        self.disable();
+        self.pdm
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    BufferTooLong,
+    BuffersDontMatch,
+}
+
+/// PDM clock frequency.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Frequency {
+    _1000K = 0x08000000,
+    _1032K = 0x08400000,
+    _1067K = 0x08800000,
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    _1231K = 0x09800000,
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    _1280K = 0x0A000000,
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    _1333K = 0x0A800000,
+}
+impl From<Frequency> for u32 {
+    fn from(variant: Frequency) -> Self {
+        variant as _
+    }
+}
+
+/// Mono or stereo operation.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Operation {
+    Stereo,
+    Mono,
+}
+impl From<Operation> for bool {
+    fn from(variant: Operation) -> Self {
+        match variant {
+            Operation::Stereo => false,
+            Operation::Mono => true,
+        }
+    }
+}
+
+/// Clock edge on which the left (or mono) channel is sampled.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Edge {
+    LeftFalling,
+    LeftRising,
+}
+impl From<Edge> for bool {
+    fn from(variant: Edge) -> Self {
+        match variant {
+            Edge::LeftFalling => false,
+            Edge::LeftRising => true,
+        }
+    }
+}
+
+/// PDM events
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum PdmEvent {
+    Started,
+    Stopped,
+    End,
+}
+
+/// A continuous double-buffered DMA stream, see [`Pdm::stream`].
+pub struct Stream<B> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<StreamInner<B>>,
+}
+
+struct StreamInner<B> {
+    buffers: [B; 2],
+    pdm: Pdm,
+    // Index of the buffer whose pointer is programmed but not yet latched.
+    next: usize,
+    // Index of the buffer being filled, `None` before the first STARTED event.
+    filling: Option<usize>,
+}
+
+impl<B> Stream<B>
+where
+    B: WriteBuffer<Word = i16>,
+{
+    /// Enables the STARTED interrupt that drives [`Stream::process`].
+    pub fn enable_interrupt(&mut self) {
+        self.inner().pdm.enable_interrupt(PdmEvent::Started);
+    }
+
+    /// Disables the STARTED interrupt.
+    pub fn disable_interrupt(&mut self) {
+        self.inner().pdm.disable_interrupt(PdmEvent::Started);
+    }
+
+    /// Handles a pending STARTED event, calling `f` with a buffer that has
+    /// just been filled.
+    ///
+    /// Each STARTED event means the PDM has latched the programmed buffer
+    /// pointer, so the other buffer is queued next. `f` has until the buffer
+    /// now being filled is full to process the samples. Returns `true` if `f`
+    /// was called.
+    ///
+    /// Call this from the PDM interrupt handler.
+    pub fn process<F>(&mut self, f: F) -> bool
+    where
+        F: FnOnce(&[i16]),
+    {
+        let inner = self.inner();
+        if !inner.pdm.is_event_triggered(PdmEvent::Started) {
+            return false;
+        }
+        inner.pdm.reset_event(PdmEvent::Started);
+
+        let done = inner.filling.replace(inner.next);
+        inner.next = 1 - inner.next;
+
+        let (ptr, _) = unsafe { inner.buffers[inner.next].write_buffer() };
+        inner
+            .pdm
+            .pdm
+            .sample
+            .ptr
+            .write(|w| unsafe { w.sampleptr().bits(ptr as u32) });
+
+        let done = match done {
+            Some(done) => done,
+            None => return false,
+        };
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions on the full buffer have completed.
+        compiler_fence(Ordering::SeqCst);
+
+        let (ptr, len) = unsafe { inner.buffers[done].write_buffer() };
+        f(unsafe { core::slice::from_raw_parts(ptr, len) });
+
+        true
+    }
+
+    /// Stops the stream and returns the buffers and the PDM.
+    pub fn stop(mut self) -> ([B; 2], Pdm) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.pdm.disable_interrupt(PdmEvent::Started);
+        inner.pdm.stop();
+        inner.pdm.reset_event(PdmEvent::Stopped);
+        compiler_fence(Ordering::Acquire);
+        (inner.buffers, inner.pdm)
+    }
+
+    fn inner(&mut self) -> &mut StreamInner<B> {
+        self.inner
+            .as_mut()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+    }
+}
+
+impl<B> Drop for Stream<B> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.pdm.stop();
+            compiler_fence(Ordering::Acquire);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! HAL interface to the POWER peripheral.
+//!
+//! Covers the reset reason, System OFF and its wake-up sources, the System ON sub power modes,
+//! the DC/DC regulators, RAM power and retention, and the general purpose retention registers
+//! (GPREGRET), which keep their value through System OFF and soft resets.
+//!
+//! The device wakes up from System OFF through a reset, with [`ResetReason`] telling what woke
+//! it up. Wake-up sources are GPIO pins (see [`Power::wake_on_pin`]), the LPCOMP (see
+//! [`lpcomp`](crate::lpcomp), on chips that have one), the NFC field and, on the nRF52833 and
+//! nRF52840, VBUS.
+
+#[cfg(any(feature = "52833", feature = "52840"))]
+use crate::pac::P1;
+use crate::{
+    gpio::{Level, Port},
+    gpiote::GpioteInputPin,
+    pac::{P0, POWER},
+};
+
+/// What caused the last reset, see `Power::reset_reason`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ResetReason {
+    /// Power-on or brown-out reset: no other reason has been recorded
+    PowerOn,
+    /// The reset pin
+    ResetPin,
+    /// The watchdog
+    Watchdog,
+    /// A soft reset (`SCB::sys_reset`)
+    SoftReset,
+    /// A CPU lock-up
+    Lockup,
+    /// Wake-up from System OFF by a GPIO pin (DETECT signal)
+    OffGpio,
+    /// Wake-up from System OFF by the LPCOMP (ANADETECT signal)
+    OffLpcomp,
+    /// Wake-up from System OFF by the debug interface
+    OffDebug,
+    /// Wake-up from System OFF by the NFC field
+    OffNfc,
+    /// Wake-up from System OFF by VBUS rising into the valid range
+    OffVbus,
+}
+
+impl ResetReason {
+    /// The reasons with their RESETREAS bit
+    const BITS: [(u32, ResetReason); 9] = [
+        (1 << 0, ResetReason::ResetPin),
+        (1 << 1, ResetReason::Watchdog),
+        (1 << 2, ResetReason::SoftReset),
+        (1 << 3, ResetReason::Lockup),
+        (1 << 16, ResetReason::OffGpio),
+        (1 << 17, ResetReason::OffLpcomp),
+        (1 << 18, ResetReason::OffDebug),
+        (1 << 19, ResetReason::OffNfc),
+        (1 << 20, ResetReason::OffVbus),
+    ];
+}
+
+/// System ON sub power mode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PowerMode {
+    /// Resources are switched on and off as needed, the default
+    LowPower,
+    /// Resources are kept on, so that the wake-up latency of the CPU and of tasks is constant
+    /// at the cost of a higher current
+    ConstantLatency,
+}
+
+/// A regulator stage that can use a DC/DC converter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Regulator {
+    /// The first stage, from VDDH, for high voltage mode
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    Reg0,
+    /// The main stage, from VDD
+    Reg1,
+}
+
+/// The POWER peripheral.
+pub struct Power(POWER);
+
+impl Power {
+    /// Takes ownership of the POWER peripheral.
+    pub fn new(power: POWER) -> Self {
+        Power(power)
+    }
+
+    /// Returns what caused the last reset.
+    ///
+    /// Reasons accumulate until they are cleared, see `clear_reset_reason`; if several are
+    /// recorded, the first one in the order of `ResetReason` is returned.
+    pub fn reset_reason(&self) -> ResetReason {
+        let bits = self.0.resetreas.read().bits();
+        ResetReason::BITS
+            .iter()
+            .find(|(mask, _)| bits & mask != 0)
+            .map_or(ResetReason::PowerOn, |&(_, reason)| reason)
+    }
+
+    /// Clears the recorded reset reasons, so that the next reset is reported on its own.
+    pub fn clear_reset_reason(&mut self) {
+        // NOTE(unsafe) the bits are cleared by writing 1 to them
+        self.0.resetreas.write(|w| unsafe { w.bits(0xFFFF_FFFF) });
+    }
+
+    /// Returns and clears the reset reason.
+    pub fn take_reset_reason(&mut self) -> ResetReason {
+        let reason = self.reset_reason();
+        self.clear_reset_reason();
+        reason
+    }
+
+    /// Configures `pin` to wake the device up from System OFF when it is at `level`.
+    ///
+    /// This sets the SENSE field of the pin, which also generates the PORT event in System ON,
+    /// see `GpiotePort`.
+    pub fn wake_on_pin<P: GpioteInputPin>(&mut self, pin: &P, level: Level) {
+        let port = match pin.port() {
+            Port::Port0 => P0::ptr(),
+            #[cfg(any(feature = "52833", feature = "52840"))]
+            Port::Port1 => P1::ptr(),
+        };
+        // NOTE(unsafe) only the SENSE field of a pin owned by the caller is modified
+        unsafe { &(*port).pin_cnf[pin.pin() as usize] }.modify(|_, w| match level {
+            Level::Low => w.sense().low(),
+            Level::High => w.sense().high(),
+        });
+    }
+
+    /// Enters System OFF, the deepest power saving mode.
+    ///
+    /// The device only leaves System OFF through a reset, either from a configured wake-up
+    /// source or from the reset pin. RAM sections are only retained if configured so, see
+    /// `set_ram_retention`.
+    ///
+    /// While a debugger is attached, System OFF is emulated and the CPU keeps running; this
+    /// function then waits forever.
+    pub fn system_off(&mut self) -> ! {
+        // Complete outstanding memory operations before the power goes away.
+        cortex_m::asm::dsb();
+        self.0.systemoff.write(|w| unsafe { w.bits(1) });
+        loop {
+            cortex_m::asm::wfe();
+        }
+    }
+
+    /// Selects the System ON sub power mode.
+    pub fn set_mode(&mut self, mode: PowerMode) {
+        match mode {
+            PowerMode::LowPower => self.0.tasks_lowpwr.write(|w| unsafe { w.bits(1) }),
+            PowerMode::ConstantLatency => self.0.tasks_constlat.write(|w| unsafe { w.bits(1) }),
+        }
+    }
+
+    /// Enables or disables the DC/DC converter of `regulator`, instead of its LDO.
+    ///
+    /// The DC/DC converter needs external components; enabling it on a board without them
+    /// makes the device stop working.
+    pub fn set_dcdc(&mut self, regulator: Regulator, enabled: bool) {
+        match regulator {
+            #[cfg(any(feature = "52833", feature = "52840"))]
+            Regulator::Reg0 => self
+                .0
+                .dcdcen0
+                .write(|w| unsafe { w.bits(u32::from(enabled)) }),
+            Regulator::Reg1 => self
+                .0
+                .dcdcen
+                .write(|w| unsafe { w.bits(u32::from(enabled)) }),
+        }
+    }
+
+    /// Sets which sections of RAM block `block` are powered in System ON, with bit `n` of
+    /// `sections` for section `n`.
+    ///
+    /// The content of a section that is switched off is lost.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block` is not a RAM block of this chip.
+    pub fn set_ram_power(&mut self, block: usize, sections: u16) {
+        let ram = &self.0.ram[block];
+        ram.power
+            .modify(|r, w| unsafe { w.bits(r.bits() & 0xFFFF_0000 | u32::from(sections)) });
+    }
+
+    /// Sets which sections of RAM block `block` are retained in System OFF, with bit `n` of
+    /// `sections` for section `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block` is not a RAM block of this chip.
+    pub fn set_ram_retention(&mut self, block: usize, sections: u16) {
+        let ram = &self.0.ram[block];
+        ram.power
+            .modify(|r, w| unsafe { w.bits(r.bits() & 0x0000_FFFF | u32::from(sections) << 16) });
+    }
+
+    /// Returns the value of the general purpose retention register GPREGRET.
+    pub fn gpregret(&self) -> u8 {
+        self.0.gpregret.read().bits() as u8
+    }
+
+    /// Sets the general purpose retention register GPREGRET.
+    pub fn set_gpregret(&mut self, value: u8) {
+        self.0
+            .gpregret
+            .write(|w| unsafe { w.bits(u32::from(value)) });
+    }
+
+    /// Returns the value of the general purpose retention register GPREGRET2.
+    pub fn gpregret2(&self) -> u8 {
+        self.0.gpregret2.read().bits() as u8
+    }
+
+    /// Sets the general purpose retention register GPREGRET2.
+    pub fn set_gpregret2(&mut self, value: u8) {
+        self.0
+            .gpregret2
+            .write(|w| unsafe { w.bits(u32::from(value)) });
+    }
+
+    /// Returns the POWER peripheral.
+    pub fn free(self) -> POWER {
+        self.0
+    }
+}
@@ -0,0 +1,918 @@
+//! HAL interface to the RADIO peripheral for Bluetooth Low Energy and proprietary Nordic modes.
+//!
+//! The packet layout is fully configurable through [`PacketConfig`] and [`CrcConfig`], so this
+//! driver can be used to implement BLE advertisers as well as proprietary protocols. For IEEE
+//! 802.15.4, use the [`ieee802154`](crate::ieee802154) module instead (on chips that support it).
+
+use core::{
+    marker::PhantomData,
+    sync::atomic::{compiler_fence, Ordering},
+};
+
+use embedded_dma::{ReadBuffer, WriteBuffer};
+use embedded_hal::timer::CountDown as _;
+
+use crate::{
+    clocks::{Clocks, ExternalOscillator},
+    pac::RADIO,
+    slice_in_ram,
+    timer::{self, Timer},
+};
+
+/// Radio driver for BLE and proprietary Nordic modes.
+pub struct Radio<'c> {
+    radio: RADIO,
+    // Number of bytes preceding the payload in RAM, given the current `PacketConfig`.
+    header_len: usize,
+    // Size of the largest packet in RAM, given the current `PacketConfig`.
+    max_packet_len: usize,
+    // Position of the LENGTH field in RAM and the mask of its bits; the mask is 0 without a
+    // LENGTH field.
+    length_pos: usize,
+    length_mask: u8,
+    // Number of payload bytes not counted by the LENGTH field, and the largest payload.
+    static_len: usize,
+    max_len: usize,
+    // used to freeze `Clocks`
+    _clocks: PhantomData<&'c ()>,
+}
+
+/// Radio data rate and modulation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    /// 1 Mbit/s Nordic proprietary radio mode
+    Nrf1Mbit,
+    /// 2 Mbit/s Nordic proprietary radio mode
+    Nrf2Mbit,
+    /// 1 Mbit/s BLE
+    Ble1Mbit,
+    /// 2 Mbit/s BLE
+    Ble2Mbit,
+    /// Long range 125 kbit/s BLE (coded PHY, S=8)
+    #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+    BleLr125Kbit,
+    /// Long range 500 kbit/s BLE (coded PHY, S=2)
+    #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+    BleLr500Kbit,
+}
+
+/// Transmission power in dBm (decibel milliwatt)
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(i8)]
+pub enum TxPower {
+    /// +8 dBm
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    Pos8dBm = 8,
+    /// +7 dBm
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    Pos7dBm = 7,
+    /// +6 dBm (~4 mW)
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    Pos6dBm = 6,
+    /// +5 dBm
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    Pos5dBm = 5,
+    /// +4 dBm
+    Pos4dBm = 4,
+    /// +3 dBm (~2 mW)
+    Pos3dBm = 3,
+    /// +2 dBm
+    #[cfg(any(feature = "52833", feature = "52840"))]
+    Pos2dBm = 2,
+    /// 0 dBm (1 mW)
+    _0dBm = 0,
+    /// -4 dBm
+    Neg4dBm = -4,
+    /// -8 dBm
+    Neg8dBm = -8,
+    /// -12 dBm
+    Neg12dBm = -12,
+    /// -16 dBm
+    Neg16dBm = -16,
+    /// -20 dBm (10 μW)
+    Neg20dBm = -20,
+    /// -40 dBm (0.1 μW)
+    Neg40dBm = -40,
+}
+
+impl TxPower {
+    /// All levels, from the highest to the lowest
+    const LEVELS: &'static [TxPower] = &[
+        #[cfg(any(feature = "52833", feature = "52840"))]
+        TxPower::Pos8dBm,
+        #[cfg(any(feature = "52833", feature = "52840"))]
+        TxPower::Pos7dBm,
+        #[cfg(any(feature = "52833", feature = "52840"))]
+        TxPower::Pos6dBm,
+        #[cfg(any(feature = "52833", feature = "52840"))]
+        TxPower::Pos5dBm,
+        TxPower::Pos4dBm,
+        TxPower::Pos3dBm,
+        #[cfg(any(feature = "52833", feature = "52840"))]
+        TxPower::Pos2dBm,
+        TxPower::_0dBm,
+        TxPower::Neg4dBm,
+        TxPower::Neg8dBm,
+        TxPower::Neg12dBm,
+        TxPower::Neg16dBm,
+        TxPower::Neg20dBm,
+        TxPower::Neg40dBm,
+    ];
+
+    /// Returns the power in dBm
+    pub fn dbm(self) -> i8 {
+        self as i8
+    }
+
+    /// Returns the highest power not above `dbm`, or the lowest power
+    pub(crate) fn at_most(dbm: i8) -> Self {
+        TxPower::LEVELS
+            .iter()
+            .copied()
+            .find(|power| power.dbm() <= dbm)
+            .unwrap_or(TxPower::Neg40dBm)
+    }
+
+    /// Returns the TXPOWER register value: the power in dBm as a two's complement byte
+    pub(crate) fn bits(self) -> u32 {
+        u32::from(self as i8 as u8)
+    }
+}
+
+/// Length of the preamble.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Preamble {
+    /// 8-bit preamble, used by the 1 Mbit/s modes
+    _8bit,
+    /// 16-bit preamble, used by the 2 Mbit/s modes
+    _16bit,
+    /// Long range preamble, required by the BLE coded PHY modes
+    #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+    LongRange,
+}
+
+/// Layout of the packets in RAM and on air (PCNF0 and PCNF1).
+///
+/// In RAM, a packet is made up of the optional S0 byte, the LENGTH field, the optional S1 field
+/// and the payload. The LENGTH, S0 and S1 fields each occupy a whole number of bytes in RAM.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacketConfig {
+    /// Length of the LENGTH field in bits, 0 to 8
+    pub length_bits: u8,
+    /// Include the S0 byte
+    pub s0_byte: bool,
+    /// Length of the S1 field in bits, 0 to 8
+    pub s1_bits: u8,
+    /// Always include the S1 field in RAM, even if its length is 0
+    pub s1_in_ram: bool,
+    /// Length of the preamble
+    pub preamble: Preamble,
+    /// Maximum length of the payload in bytes; longer packets are truncated
+    pub max_len: u8,
+    /// Number of bytes always added to the payload length in the LENGTH field
+    pub static_len: u8,
+    /// Length of the base address in bytes, 2 to 4
+    pub base_address_len: u8,
+    /// Transmit and receive the S0, LENGTH, S1 and payload fields most significant bit first
+    pub big_endian: bool,
+    /// Enable data whitening, see `Radio::set_whitening_iv`
+    pub whitening: bool,
+}
+
+impl PacketConfig {
+    /// Returns the number of bytes preceding the payload in RAM.
+    fn header_len(&self) -> usize {
+        let s1 = self.s1_bits > 0 || self.s1_in_ram;
+        usize::from(self.s0_byte) + usize::from(self.length_bits > 0) + usize::from(s1)
+    }
+}
+
+impl Default for PacketConfig {
+    /// The BLE 1 Mbit/s advertising channel PDU layout: an S0 byte holding the header flags, an
+    /// 8-bit LENGTH field, a 3 byte base address and whitening.
+    fn default() -> Self {
+        Self {
+            length_bits: 8,
+            s0_byte: true,
+            s1_bits: 0,
+            s1_in_ram: false,
+            preamble: Preamble::_8bit,
+            max_len: 255,
+            static_len: 0,
+            base_address_len: 3,
+            big_endian: false,
+            whitening: true,
+        }
+    }
+}
+
+/// CRC configuration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CrcConfig {
+    /// Length of the CRC in bytes, 0 (disabled) to 3
+    pub len: u8,
+    /// Exclude the address field from the CRC calculation
+    pub skip_address: bool,
+    /// CRC polynomial, with the coefficient of x^0 in bit 0; the highest term is implicit
+    pub polynomial: u32,
+    /// Initial value of the CRC
+    pub init: u32,
+}
+
+impl CrcConfig {
+    /// The BLE CRC: 3 bytes, polynomial x^24 + x^10 + x^9 + x^6 + x^4 + x^3 + x + 1, address not
+    /// included, starting from `init` (0x555555 on the advertising channels).
+    pub const fn ble(init: u32) -> Self {
+        Self {
+            len: 3,
+            skip_address: true,
+            polynomial: 0x00065B,
+            init,
+        }
+    }
+}
+
+impl<'c> Radio<'c> {
+    /// Initializes the radio, configured for BLE 1 Mbit/s advertising packets on 2402 MHz.
+    ///
+    /// Takes a `Clocks` reference to make sure the high frequency crystal oscillator is running,
+    /// which the RADIO requires.
+    pub fn new<L, LSTAT>(radio: RADIO, _clocks: &'c Clocks<ExternalOscillator, L, LSTAT>) -> Self {
+        let mut radio = Self {
+            radio,
+            header_len: 0,
+            max_packet_len: 0,
+            length_pos: 0,
+            length_mask: 0,
+            static_len: 0,
+            max_len: 0,
+            _clocks: PhantomData,
+        };
+
+        // shortcuts will be kept off by default and only be temporarily enabled by the functions
+        // starting a transfer
+        radio.radio.shorts.reset();
+
+        // go to a known state
+        radio.disable();
+
+        radio.set_mode(Mode::Ble1Mbit);
+        radio.set_packet_config(&PacketConfig::default());
+        radio.set_crc(CrcConfig::ble(0x555555));
+        radio.set_frequency(2402);
+        radio.set_whitening_iv(37);
+        radio.set_txpower(TxPower::_0dBm);
+
+        radio
+    }
+
+    /// Changes the data rate and modulation.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.radio.mode.write(|w| match mode {
+            Mode::Nrf1Mbit => w.mode().nrf_1mbit(),
+            Mode::Nrf2Mbit => w.mode().nrf_2mbit(),
+            Mode::Ble1Mbit => w.mode().ble_1mbit(),
+            Mode::Ble2Mbit => w.mode().ble_2mbit(),
+            #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+            Mode::BleLr125Kbit => w.mode().ble_lr125kbit(),
+            #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+            Mode::BleLr500Kbit => w.mode().ble_lr500kbit(),
+        });
+    }
+
+    /// Changes the packet layout.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a field length is out of range.
+    pub fn set_packet_config(&mut self, config: &PacketConfig) {
+        assert!(config.length_bits <= 8 && config.s1_bits <= 8);
+        assert!((2..=4).contains(&config.base_address_len));
+
+        let (plen, cilen, termlen) = match config.preamble {
+            Preamble::_8bit => (0, 0, 0),
+            Preamble::_16bit => (1, 0, 0),
+            // The coded PHY uses a 2 bit code indicator and a 3 bit TERM1 field.
+            #[cfg(any(feature = "52811", feature = "52833", feature = "52840"))]
+            Preamble::LongRange => (3, 2, 3),
+        };
+
+        // NOTE(unsafe) PCNF0/PCNF1 are written as a whole, since the available fields differ
+        // between chips
+        self.radio.pcnf0.write(|w| unsafe {
+            w.bits(
+                u32::from(config.length_bits)
+                    | u32::from(config.s0_byte) << 8
+                    | u32::from(config.s1_bits) << 16
+                    | u32::from(config.s1_in_ram) << 20
+                    | cilen << 22
+                    | plen << 24
+                    | termlen << 29,
+            )
+        });
+        self.radio.pcnf1.write(|w| unsafe {
+            w.bits(
+                u32::from(config.max_len)
+                    | u32::from(config.static_len) << 8
+                    | u32::from(config.base_address_len) << 16
+                    | u32::from(config.big_endian) << 24
+                    | u32::from(config.whitening) << 25,
+            )
+        });
+
+        self.header_len = config.header_len();
+        self.max_packet_len = self.header_len + usize::from(config.max_len);
+        self.length_pos = usize::from(config.s0_byte);
+        self.length_mask = (0xFF_u16 >> (8 - config.length_bits)) as u8;
+        self.static_len = usize::from(config.static_len);
+        self.max_len = usize::from(config.max_len);
+    }
+
+    /// Changes the CRC configuration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.len` is larger than 3.
+    pub fn set_crc(&mut self, config: CrcConfig) {
+        assert!(config.len <= 3);
+        self.radio.crccnf.write(|w| unsafe {
+            w.bits(u32::from(config.len) | u32::from(config.skip_address) << 8)
+        });
+        self.radio
+            .crcpoly
+            .write(|w| unsafe { w.crcpoly().bits(config.polynomial & 0xFF_FFFF) });
+        self.radio
+            .crcinit
+            .write(|w| unsafe { w.crcinit().bits(config.init & 0xFF_FFFF) });
+    }
+
+    /// Changes the radio frequency, in MHz.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mhz` is not within 2360 to 2500 MHz.
+    pub fn set_frequency(&mut self, mhz: u16) {
+        assert!((2360..=2500).contains(&mhz));
+        let (map, offset) = if mhz >= 2400 {
+            (false, mhz - 2400)
+        } else {
+            (true, mhz - 2360)
+        };
+        self.radio.frequency.write(|w| unsafe {
+            w.map().bit(map);
+            w.frequency().bits(offset as u8)
+        });
+    }
+
+    /// Changes the TX power.
+    pub fn set_txpower(&mut self, power: TxPower) {
+        // NOTE(unsafe) every `TxPower` is a valid TXPOWER value
+        self.radio
+            .txpower
+            .write(|w| unsafe { w.bits(power.bits()) });
+    }
+
+    /// Changes the data whitening initial value.
+    ///
+    /// For BLE, this is the channel index (0 to 39).
+    pub fn set_whitening_iv(&mut self, iv: u8) {
+        // bit 6 of the register is always 1
+        self.radio
+            .datawhiteiv
+            .write(|w| unsafe { w.datawhiteiv().bits(iv | 0x40) });
+    }
+
+    /// Sets base address 0 (used by logical address 0) or 1 (used by logical addresses 1 to 7).
+    ///
+    /// The least significant `base_address_len` bytes of `address` are used.
+    pub fn set_base_address(&mut self, index: u8, address: u32) {
+        match index {
+            0 => self.radio.base0.write(|w| unsafe { w.bits(address) }),
+            1 => self.radio.base1.write(|w| unsafe { w.bits(address) }),
+            _ => panic!("base address index must be 0 or 1"),
+        }
+    }
+
+    /// Sets the address prefix byte of `logical_address` (0 to 7).
+    pub fn set_prefix(&mut self, logical_address: u8, prefix: u8) {
+        assert!(logical_address < 8);
+        let (register, shift) = if logical_address < 4 {
+            (&self.radio.prefix0, logical_address * 8)
+        } else {
+            (&self.radio.prefix1, (logical_address - 4) * 8)
+        };
+        register.modify(|r, w| unsafe {
+            w.bits(r.bits() & !(0xFF << shift) | u32::from(prefix) << shift)
+        });
+    }
+
+    /// Selects the logical address (0 to 7) used when transmitting.
+    pub fn set_tx_address(&mut self, logical_address: u8) {
+        assert!(logical_address < 8);
+        self.radio
+            .txaddress
+            .write(|w| unsafe { w.txaddress().bits(logical_address) });
+    }
+
+    /// Selects the logical addresses to receive on, with bit `n` of `mask` enabling logical
+    /// address `n`.
+    pub fn set_rx_addresses(&mut self, mask: u8) {
+        self.radio
+            .rxaddresses
+            .write(|w| unsafe { w.bits(mask.into()) });
+    }
+
+    /// Returns the RSSI of the last received packet in dBm.
+    pub fn rssi(&self) -> i8 {
+        -(self.radio.rssisample.read().rssisample().bits() as i8)
+    }
+
+    /// Returns the logical address the last packet has been received on.
+    pub fn rx_address(&self) -> u8 {
+        self.radio.rxmatch.read().rxmatch().bits()
+    }
+
+    /// Transmits `packet` and blocks until the transmission has completed.
+    ///
+    /// `packet` has to be laid out according to the current `PacketConfig`, starting with the S0
+    /// byte (if any) and the LENGTH field. Returns `Error::BufferTooSmall` if the payload given by
+    /// the LENGTH field and the static length doesn't fit `packet`, or exceeds the maximum length.
+    pub fn send(&mut self, packet: &[u8]) -> Result<(), Error> {
+        self.check_buffer(packet)?;
+
+        // NOTE(unsafe) We block until the transmission completes
+        unsafe { self.start(packet.as_ptr() as u32, false) };
+        self.wait_disabled();
+
+        Ok(())
+    }
+
+    /// Receives a packet into `packet`, blocking until a packet has been received.
+    ///
+    /// `packet` has to be large enough to hold the largest packet of the current
+    /// `PacketConfig`. The RSSI is sampled during reception, see `rssi`.
+    pub fn recv(&mut self, packet: &mut [u8]) -> Result<(), Error> {
+        self.check_rx_buffer(packet)?;
+
+        // NOTE(unsafe) We block until reception completes
+        unsafe { self.start(packet.as_mut_ptr() as u32, true) };
+        self.wait_disabled();
+
+        self.crc_result()
+    }
+
+    /// Listens for a packet for no longer than the specified amount of microseconds and receives
+    /// it into `packet`.
+    ///
+    /// If no packet is received within the specified time then the `Timeout` error is returned.
+    /// Note that the radio ramp-up time is included in the timeout count.
+    pub fn recv_timeout<I>(
+        &mut self,
+        packet: &mut [u8],
+        timer: &mut Timer<I>,
+        microseconds: u32,
+    ) -> Result<(), Error>
+    where
+        I: timer::Instance,
+    {
+        self.check_rx_buffer(packet)?;
+
+        // Start the timeout timer
+        timer.start(microseconds);
+
+        // NOTE(unsafe) We block until reception completes or is cancelled
+        unsafe { self.start(packet.as_mut_ptr() as u32, true) };
+
+        loop {
+            if self.radio.events_disabled.read().bits() != 0 {
+                self.radio.events_disabled.reset();
+                self.radio.shorts.reset();
+                compiler_fence(Ordering::Acquire);
+                return self.crc_result();
+            }
+
+            if timer.wait().is_ok() {
+                self.disable();
+                return Err(Error::Timeout);
+            }
+        }
+    }
+
+    /// Starts transmitting the packet in `buffer`, returning a value that represents the
+    /// in-progress transmission.
+    ///
+    /// See `send` for the layout of the packet. Use `enable_interrupt` and `Transfer::is_done`
+    /// to be notified when the transmission has completed.
+    pub fn send_dma<B>(mut self, buffer: B) -> Result<Transfer<'c, B>, (Error, Self, B)>
+    where
+        B: ReadBuffer<Word = u8> + 'static,
+    {
+        // NOTE(unsafe) The buffer is 'static and owned by the transfer until it completes
+        let (ptr, len) = unsafe { buffer.read_buffer() };
+        // NOTE(unsafe) The pointer and length come from a valid slice
+        let slice = unsafe { core::slice::from_raw_parts(ptr, len) };
+        if let Err(e) = self.check_buffer(slice) {
+            return Err((e, self, buffer));
+        }
+
+        // NOTE(unsafe) The transfer owns the buffer and stops the radio if it is dropped
+        unsafe { self.start(ptr as u32, false) };
+
+        Ok(Transfer {
+            inner: Some(Inner {
+                radio: self,
+                buffer,
+                rx: false,
+            }),
+        })
+    }
+
+    /// Starts receiving a packet into `buffer`, returning a value that represents the
+    /// in-progress reception.
+    ///
+    /// See `recv` for the requirements on `buffer`. Use `enable_interrupt` and
+    /// `Transfer::is_done` to be notified when a packet has been received.
+    pub fn recv_dma<B>(mut self, mut buffer: B) -> Result<Transfer<'c, B>, (Error, Self, B)>
+    where
+        B: WriteBuffer<Word = u8> + 'static,
+    {
+        // NOTE(unsafe) The buffer is 'static and owned by the transfer until it completes
+        let (ptr, len) = unsafe { buffer.write_buffer() };
+        if len < self.max_packet_len {
+            return Err((Error::BufferTooSmall, self, buffer));
+        }
+
+        // NOTE(unsafe) The transfer owns the buffer and stops the radio if it is dropped
+        unsafe { self.start(ptr as u32, true) };
+
+        Ok(Transfer {
+            inner: Some(Inner {
+                radio: self,
+                buffer,
+                rx: true,
+            }),
+        })
+    }
+
+    /// Starts transmitting an unmodulated carrier on `mhz` with the given TX power, for RF
+    /// certification and production tests.
+    ///
+    /// The carrier is emitted until the returned [`Carrier`] is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mhz` is not within 2360 to 2500 MHz.
+    pub fn start_constant_carrier(&mut self, mhz: u16, power: TxPower) -> Carrier<'_, 'c> {
+        self.set_frequency(mhz);
+        self.set_txpower(power);
+
+        // Once ramped up, the transmitter sends the carrier until the START task is triggered.
+        self.radio.shorts.reset();
+        self.radio.events_ready.reset();
+        self.radio.tasks_txen.write(|w| unsafe { w.bits(1) });
+        while self.radio.events_ready.read().bits() == 0 {}
+        self.radio.events_ready.reset();
+
+        Carrier {
+            radio: self,
+            _buffer: PhantomData,
+        }
+    }
+
+    /// Starts transmitting a carrier on `mhz` with the given TX power, continuously modulated
+    /// with a PRBS9 sequence.
+    ///
+    /// `buffer` is filled with the PRBS9 sequence and sent back-to-back, using the current
+    /// mode, preamble, address and CRC. The packet layout is changed to a payload of
+    /// `buffer.len()` bytes without LENGTH, S0 and S1 fields and without whitening, so call
+    /// `set_packet_config` before using the radio for anything else.
+    ///
+    /// The carrier is emitted until the returned [`Carrier`] is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mhz` is not within 2360 to 2500 MHz, or if `buffer` is empty or longer than
+    /// 255 bytes.
+    pub fn start_modulated_carrier<'a>(
+        &'a mut self,
+        mhz: u16,
+        power: TxPower,
+        buffer: &'a mut [u8],
+    ) -> Result<Carrier<'a, 'c>, Error> {
+        assert!((1..=255).contains(&buffer.len()));
+        if !slice_in_ram(buffer) {
+            return Err(Error::BufferNotInRAM);
+        }
+
+        self.set_frequency(mhz);
+        self.set_txpower(power);
+        prbs9(buffer);
+
+        let len = buffer.len() as u32;
+        // NOTE(unsafe) Only the field lengths are cleared, keeping the preamble configuration;
+        // the payload length is static, and the base address length and endianness are kept.
+        self.radio
+            .pcnf0
+            .modify(|r, w| unsafe { w.bits(r.bits() & !0x003F_010F) });
+        self.radio
+            .pcnf1
+            .modify(|r, w| unsafe { w.bits(r.bits() & 0x0107_0000 | len << 8 | len) });
+        self.header_len = 0;
+        self.max_packet_len = buffer.len();
+        self.length_mask = 0;
+        self.static_len = buffer.len();
+        self.max_len = buffer.len();
+
+        self.radio.events_ready.reset();
+        self.radio.events_end.reset();
+        self.radio.events_disabled.reset();
+        self.radio
+            .packetptr
+            .write(|w| unsafe { w.bits(buffer.as_ptr() as u32) });
+        // Restart the transmission at the end of each packet, so that it never stops.
+        self.radio
+            .shorts
+            .write(|w| w.ready_start().enabled().end_start().enabled());
+
+        compiler_fence(Ordering::Release);
+        self.radio.tasks_txen.write(|w| unsafe { w.bits(1) });
+
+        Ok(Carrier {
+            radio: self,
+            _buffer: PhantomData,
+        })
+    }
+
+    /// Measures the RSSI on every MHz from 2400 to 2483 MHz, returning the power in dBm indexed
+    /// by the offset from 2400 MHz.
+    ///
+    /// On each frequency, the RSSI is sampled `samples` times and the highest power is kept. The
+    /// frequency is restored afterwards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is 0.
+    pub fn rssi_sweep(&mut self, samples: u8) -> [i8; RSSI_SWEEP_LEN] {
+        assert!(samples > 0);
+
+        let frequency = self.radio.frequency.read().bits();
+        let mut table = [i8::MIN; RSSI_SWEEP_LEN];
+
+        for (mhz, power) in (2400..).zip(table.iter_mut()) {
+            self.set_frequency(mhz);
+
+            self.radio.events_ready.reset();
+            self.radio.shorts.write(|w| w.ready_start().enabled());
+            self.radio.tasks_rxen.write(|w| unsafe { w.bits(1) });
+            while self.radio.events_ready.read().bits() == 0 {}
+            self.radio.events_ready.reset();
+
+            for _ in 0..samples {
+                self.radio.events_rssiend.reset();
+                self.radio.tasks_rssistart.write(|w| unsafe { w.bits(1) });
+                while self.radio.events_rssiend.read().bits() == 0 {}
+                self.radio.events_rssiend.reset();
+                *power = (*power).max(self.rssi());
+            }
+
+            self.disable();
+        }
+
+        // NOTE(unsafe) restores a value read from the register
+        self.radio.frequency.write(|w| unsafe { w.bits(frequency) });
+        table
+    }
+
+    /// Enables the interrupt signalling the end of a transfer (the DISABLED event).
+    pub fn enable_interrupt(&mut self) {
+        self.radio.intenset.write(|w| w.disabled().set());
+    }
+
+    /// Disables the interrupt signalling the end of a transfer.
+    pub fn disable_interrupt(&mut self) {
+        self.radio.intenclr.write(|w| w.disabled().clear());
+    }
+
+    /// Returns the registers, for protocol implementations within the HAL that need precise
+    /// control over the shortcuts.
+    pub(crate) fn regs(&self) -> &RADIO {
+        &self.radio
+    }
+
+    /// Return the raw interface to the underlying RADIO peripheral.
+    pub fn free(mut self) -> RADIO {
+        self.disable();
+        self.radio
+    }
+
+    fn check_buffer(&self, packet: &[u8]) -> Result<(), Error> {
+        if !slice_in_ram(packet) {
+            return Err(Error::BufferNotInRAM);
+        }
+        if packet.len() < self.header_len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let length = if self.length_mask == 0 {
+            0
+        } else {
+            usize::from(packet[self.length_pos] & self.length_mask)
+        };
+        let payload_len = length + self.static_len;
+        if payload_len > self.max_len || self.header_len + payload_len > packet.len() {
+            return Err(Error::BufferTooSmall);
+        }
+        Ok(())
+    }
+
+    fn check_rx_buffer(&self, packet: &[u8]) -> Result<(), Error> {
+        if packet.len() < self.max_packet_len {
+            return Err(Error::BufferTooSmall);
+        }
+        Ok(())
+    }
+
+    /// Ramps up the radio and starts a transfer from or to `ptr`. The radio disables itself once
+    /// the packet has been sent or received.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` has to point to a buffer that remains valid until the transfer has completed or has
+    /// been stopped.
+    unsafe fn start(&mut self, ptr: u32, rx: bool) {
+        self.radio.events_ready.reset();
+        self.radio.events_end.reset();
+        self.radio.events_disabled.reset();
+
+        self.radio.packetptr.write(|w| w.bits(ptr));
+
+        if rx {
+            // Sample the RSSI once the address has been received
+            self.radio.shorts.write(|w| {
+                w.ready_start()
+                    .enabled()
+                    .end_disable()
+                    .enabled()
+                    .address_rssistart()
+                    .enabled()
+                    .disabled_rssistop()
+                    .enabled()
+            });
+        } else {
+            self.radio
+                .shorts
+                .write(|w| w.ready_start().enabled().end_disable().enabled());
+        }
+
+        // Conservative compiler fence to prevent starting the transfer before the buffer has
+        // been written.
+        compiler_fence(Ordering::Release);
+
+        if rx {
+            self.radio.tasks_rxen.write(|w| w.bits(1));
+        } else {
+            self.radio.tasks_txen.write(|w| w.bits(1));
+        }
+    }
+
+    fn wait_disabled(&mut self) {
+        while self.radio.events_disabled.read().bits() == 0 {}
+        self.radio.events_disabled.reset();
+        self.radio.shorts.reset();
+
+        // Conservative compiler fence to prevent optimizations that do not take in to account
+        // actions by DMA. The fence has been placed here, after all possible DMA actions have
+        // completed.
+        compiler_fence(Ordering::Acquire);
+    }
+
+    fn crc_result(&self) -> Result<(), Error> {
+        if self.radio.crcstatus.read().crcstatus().bit_is_set() {
+            Ok(())
+        } else {
+            Err(Error::Crc)
+        }
+    }
+
+    /// Moves the radio from any state to the DISABLED state, stopping an ongoing transfer.
+    fn disable(&mut self) {
+        self.radio.shorts.reset();
+        self.radio.events_disabled.reset();
+        self.radio.tasks_disable.write(|w| unsafe { w.bits(1) });
+        while self.radio.events_disabled.read().bits() == 0 {}
+        self.radio.events_disabled.reset();
+
+        // A transfer may have been in progress so synchronize with its memory operations
+        compiler_fence(Ordering::Acquire);
+    }
+}
+
+/// An in-progress transmission or reception, see `Radio::send_dma` and `Radio::recv_dma`.
+pub struct Transfer<'c, B> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<Inner<'c, B>>,
+}
+
+struct Inner<'c, B> {
+    radio: Radio<'c>,
+    buffer: B,
+    rx: bool,
+}
+
+impl<'c, B> Transfer<'c, B> {
+    /// Returns `true` if the packet has been sent or received.
+    pub fn is_done(&self) -> bool {
+        let inner = self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.radio.radio.events_disabled.read().bits() != 0
+    }
+
+    /// Blocks until the transfer is done and returns the radio and the buffer.
+    ///
+    /// For a reception, the result reports whether the CRC of the received packet was valid.
+    pub fn wait(mut self) -> (Radio<'c>, B, Result<(), Error>) {
+        let mut inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.radio.wait_disabled();
+
+        let result = if inner.rx {
+            inner.radio.crc_result()
+        } else {
+            Ok(())
+        };
+        (inner.radio, inner.buffer, result)
+    }
+
+    /// Stops the transfer, returning the radio and the buffer.
+    pub fn cancel(mut self) -> (Radio<'c>, B) {
+        let mut inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.radio.disable();
+        (inner.radio, inner.buffer)
+    }
+}
+
+impl<'c, B> Drop for Transfer<'c, B> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.radio.disable();
+        }
+    }
+}
+
+/// A carrier being transmitted for testing, see `Radio::start_constant_carrier` and
+/// `Radio::start_modulated_carrier`.
+///
+/// The transmission is stopped when this value is dropped.
+pub struct Carrier<'a, 'c> {
+    radio: &'a mut Radio<'c>,
+    // the buffer of a modulated carrier, read by EasyDMA until the radio is disabled
+    _buffer: PhantomData<&'a [u8]>,
+}
+
+impl<'a, 'c> Carrier<'a, 'c> {
+    /// Stops the transmission.
+    pub fn stop(self) {}
+}
+
+impl<'a, 'c> Drop for Carrier<'a, 'c> {
+    fn drop(&mut self) {
+        self.radio.disable();
+    }
+}
+
+/// Number of entries returned by `Radio::rssi_sweep`, one per MHz from 2400 to 2483 MHz.
+pub const RSSI_SWEEP_LEN: usize = 84;
+
+/// Fills `buffer` with the PRBS9 sequence (x^9 + x^5 + 1, all ones seed), least significant bit
+/// first, as used by the Bluetooth Direct Test Mode.
+fn prbs9(buffer: &mut [u8]) {
+    let mut state: u16 = 0x1FF;
+    for byte in buffer {
+        *byte = 0;
+        for bit in 0..8 {
+            *byte |= ((state & 1) as u8) << bit;
+            let feedback = (state ^ (state >> 4)) & 1;
+            state = state >> 1 | feedback << 8;
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The CRC of the received packet was invalid
+    Crc,
+    /// No packet was received in time
+    Timeout,
+    /// The packet buffer is not located in RAM, which EasyDMA requires
+    BufferNotInRAM,
+    /// The packet buffer is too small for the configured packet layout, or the payload length
+    /// given by a packet to send doesn't fit its buffer or the configured maximum length
+    BufferTooSmall,
+}
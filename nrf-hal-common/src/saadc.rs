@@ -44,14 +44,76 @@ pub use saadc::{
     resolution::VAL_A as Resolution,
 };
 
-// Only 1 channel is allowed right now, a discussion needs to be had as to how
-// multiple channels should work (See "scan mode" in the datasheet).
-// Issue: https://github.com/nrf-rs/nrf-hal/issues/82
+/// Implements the limit event methods for a type with a `regs` method
+/// returning the SAADC registers.
+macro_rules! impl_limits {
+    () => {
+        /// Sets the limits of the channel at index `channel`.
+        ///
+        /// The LIMITH and LIMITL events are generated whenever a sample of
+        /// the channel is above `high` or below `low`, respectively.
+        pub fn set_limits(&mut self, channel: usize, low: i16, high: i16) {
+            self.regs().ch[channel]
+                .limit
+                .write(|w| unsafe { w.low().bits(low as u16).high().bits(high as u16) });
+        }
+
+        /// Enables the interrupt for the `limit` event of `channel`.
+        pub fn enable_limit_interrupt(&mut self, channel: usize, limit: Limit) {
+            assert!(channel < 8);
+            self.regs()
+                .intenset
+                .write(|w| unsafe { w.bits(limit.inten_mask(channel)) });
+        }
+
+        /// Disables the interrupt for the `limit` event of `channel`.
+        pub fn disable_limit_interrupt(&mut self, channel: usize, limit: Limit) {
+            assert!(channel < 8);
+            self.regs()
+                .intenclr
+                .write(|w| unsafe { w.bits(limit.inten_mask(channel)) });
+        }
+
+        /// Returns `true` if the `limit` event of `channel` has been generated.
+        pub fn is_limit_event(&self, channel: usize, limit: Limit) -> bool {
+            let event = &self.regs().events_ch[channel];
+            match limit {
+                Limit::High => event.limith.read().bits() != 0,
+                Limit::Low => event.limitl.read().bits() != 0,
+            }
+        }
+
+        /// Resets the `limit` event of `channel`.
+        pub fn reset_limit_event(&mut self, channel: usize, limit: Limit) {
+            let event = &self.regs().events_ch[channel];
+            match limit {
+                Limit::High => event.limith.reset(),
+                Limit::Low => event.limitl.reset(),
+            }
+        }
+
+        /// Returns reference to the LIMITH event endpoint of `channel` for PPI.
+        #[inline(always)]
+        pub fn event_limith(&self, channel: usize) -> &saadc::events_ch::LIMITH {
+            &self.regs().events_ch[channel].limith
+        }
+
+        /// Returns reference to the LIMITL event endpoint of `channel` for PPI.
+        #[inline(always)]
+        pub fn event_limitl(&self, channel: usize) -> &saadc::events_ch::LIMITL {
+            &self.regs().events_ch[channel].limitl
+        }
+    };
+}
 
 /// Interface for the SAADC peripheral.
 ///
 /// External analog channels supported by the SAADC implement the `Channel` trait.
-/// Currently, use of only one channel is allowed.
+/// `OneShot` reads sample a single channel. Use [`Saadc::scan`] to sample up to
+/// eight channels at once.
+///
+/// One-shot reads use channel 0, so its limits are the ones checked by the
+/// limit events.
 pub struct Saadc(SAADC);
 
 impl Saadc {
@@ -86,18 +148,496 @@ impl Saadc {
         });
         saadc.ch[0].pseln.write(|w| w.pseln().nc());
 
-        // Calibrate
-        saadc.events_calibratedone.reset();
-        saadc.tasks_calibrateoffset.write(|w| unsafe { w.bits(1) });
-        while saadc.events_calibratedone.read().bits() == 0 {}
+        calibrate_offset(&saadc);
 
         Saadc(saadc)
     }
+
+    /// Run an offset calibration.
+    ///
+    /// The offset is calibrated once by `new`. Calibrate again when the
+    /// temperature has changed significantly, e.g. as reported by `temp::Temp`.
+    /// The resulting offset is kept by the SAADC and applied to all following
+    /// samples.
+    pub fn calibrate(&mut self) {
+        calibrate_offset(&self.0);
+    }
+
+    #[inline(always)]
+    fn regs(&self) -> &saadc::RegisterBlock {
+        &self.0
+    }
+
+    impl_limits!();
+
+    /// Configure scan mode, sampling all of `channels` for each SAMPLE task.
+    ///
+    /// The results of a scan are stored in the order of `channels`, one `i16`
+    /// per channel. The resolution and oversampling of the `SaadcConfig` passed
+    /// to `new` are kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channels` is empty or holds more than 8 channels.
+    pub fn scan<I>(self, channels: I) -> SaadcScan
+    where
+        I: IntoIterator<Item = ChannelConfig>,
+    {
+        let mut channels = channels.into_iter();
+        let mut count = 0;
+
+        for ch in self.0.ch.iter() {
+            match channels.next() {
+                Some(ChannelConfig {
+                    positive,
+                    negative,
+                    reference,
+                    gain,
+                    resistor,
+                    time,
+                }) => {
+                    ch.config.write(|w| {
+                        w.refsel().variant(reference);
+                        w.gain().variant(gain);
+                        w.tacq().variant(time);
+                        w.resp().variant(resistor);
+                        w.resn().bypass();
+                        w.burst().enabled();
+                        match negative {
+                            Some(_) => w.mode().diff(),
+                            None => w.mode().se(),
+                        }
+                    });
+                    ch.pselp.write(|w| unsafe { w.bits(psel_bits(positive)) });
+                    match negative {
+                        Some(negative) => {
+                            ch.pseln.write(|w| unsafe { w.bits(psel_bits(negative)) })
+                        }
+                        None => ch.pseln.write(|w| w.pseln().nc()),
+                    }
+                    count += 1;
+                }
+                None => {
+                    // A channel is enabled by selecting a positive input.
+                    ch.pselp.write(|w| w.pselp().nc());
+                    ch.pseln.write(|w| w.pseln().nc());
+                }
+            }
+        }
+
+        assert!(count > 0, "at least one channel must be configured");
+        assert!(
+            channels.next().is_none(),
+            "at most 8 channels can be configured"
+        );
+
+        SaadcScan {
+            saadc: self.0,
+            channels: count,
+        }
+    }
+}
+
+fn calibrate_offset(saadc: &saadc::RegisterBlock) {
+    saadc.events_calibratedone.reset();
+    saadc.tasks_calibrateoffset.write(|w| unsafe { w.bits(1) });
+    while saadc.events_calibratedone.read().bits() == 0 {}
+    saadc.events_calibratedone.reset();
+}
+
+/// Returns the PSELP/PSELN value selecting the input with `Channel` ID `id`.
+fn psel_bits(id: u8) -> u32 {
+    match id {
+        // AnalogInput0 to AnalogInput7, and VDD.
+        0..=8 => u32::from(id) + 1,
+        // VDDHDIV5 uses its register value as ID.
+        _ => u32::from(id),
+    }
+}
+
+/// Configuration of a channel sampled in scan mode, see [`Saadc::scan`].
+pub struct ChannelConfig {
+    positive: u8,
+    negative: Option<u8>,
+    /// Reference voltage of the channel.
+    pub reference: Reference,
+    /// Gain used to control the effective input range of the channel.
+    pub gain: Gain,
+    /// Positive channel resistor control.
+    pub resistor: Resistor,
+    /// Acquisition time in microseconds.
+    pub time: Time,
+}
+
+impl ChannelConfig {
+    /// Single-ended channel sampling `positive`, with the gain, reference,
+    /// resistor and acquisition time of `SaadcConfig::default()`.
+    pub fn single_ended<P>(_positive: &P) -> Self
+    where
+        P: Channel<Saadc, ID = u8>,
+    {
+        Self::new(P::channel(), None)
+    }
+
+    /// Differential channel sampling `positive` against `negative`, with the
+    /// gain, reference, resistor and acquisition time of `SaadcConfig::default()`.
+    pub fn differential<P, N>(_positive: &P, _negative: &N) -> Self
+    where
+        P: Channel<Saadc, ID = u8>,
+        N: Channel<Saadc, ID = u8>,
+    {
+        Self::new(P::channel(), Some(N::channel()))
+    }
+
+    /// Converts a raw result of this channel to millivolts.
+    ///
+    /// `resolution` is the one of the `SaadcConfig` passed to `Saadc::new`.
+    /// `vdd_mv` is the supply voltage in millivolts, which is only used with
+    /// `Reference::VDD1_4`.
+    pub fn to_millivolts(&self, raw: i16, resolution: &Resolution, vdd_mv: u16) -> i32 {
+        to_millivolts(
+            raw,
+            &self.gain,
+            &self.reference,
+            resolution,
+            self.negative.is_some(),
+            vdd_mv,
+        )
+    }
+
+    fn new(positive: u8, negative: Option<u8>) -> Self {
+        let SaadcConfig {
+            reference,
+            gain,
+            resistor,
+            time,
+            ..
+        } = SaadcConfig::default();
+
+        Self {
+            positive,
+            negative,
+            reference,
+            gain,
+            resistor,
+            time,
+        }
+    }
+}
+
+/// Which of a channel's limits to monitor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Limit {
+    /// The LIMITH event, generated when a sample is above the high limit.
+    High,
+    /// The LIMITL event, generated when a sample is below the low limit.
+    Low,
+}
+
+impl Limit {
+    /// Returns the INTEN bit of this limit event of `channel`.
+    fn inten_mask(self, channel: usize) -> u32 {
+        match self {
+            Limit::High => 1 << (6 + 2 * channel),
+            Limit::Low => 1 << (7 + 2 * channel),
+        }
+    }
+}
+
+/// The SAADC in scan mode, see [`Saadc::scan`].
+pub struct SaadcScan {
+    saadc: SAADC,
+    channels: usize,
+}
+
+impl SaadcScan {
+    /// Returns the number of configured channels.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    #[inline(always)]
+    fn regs(&self) -> &saadc::RegisterBlock {
+        &self.saadc
+    }
+
+    /// Run an offset calibration, see [`Saadc::calibrate`].
+    pub fn calibrate(&mut self) {
+        calibrate_offset(&self.saadc);
+    }
+
+    /// Sample all channels once, storing one result per channel in `buffer`.
+    /// Note that this is a blocking operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is shorter than the number of channels.
+    pub fn read(&mut self, buffer: &mut [i16]) {
+        assert!(buffer.len() >= self.channels);
+
+        self.saadc
+            .result
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffer.as_mut_ptr() as u32) });
+        self.saadc
+            .result
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(self.channels as _) });
+
+        // Conservative compiler fence to prevent starting the ADC before the
+        // pointer and maxcount have been set.
+        compiler_fence(SeqCst);
+
+        self.saadc.tasks_start.write(|w| unsafe { w.bits(1) });
+        self.saadc.tasks_sample.write(|w| unsafe { w.bits(1) });
+
+        while self.saadc.events_end.read().bits() == 0 {}
+        self.saadc.events_end.reset();
+
+        // Second fence to prevent optimizations creating issues with the EasyDMA-modified buffer.
+        compiler_fence(SeqCst);
+    }
+
+    /// Start continuous sampling into two alternating buffers.
+    ///
+    /// Each buffer holds whole scans, so its length must be a multiple of the
+    /// number of channels. Use [`SaadcContinuous::process`] from the SAADC
+    /// interrupt to hand over full buffers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a buffer is empty, too long for EasyDMA or not a multiple of
+    /// the number of channels, or if `SampleTrigger::Internal` is used with more
+    /// than one channel.
+    pub fn into_continuous(
+        self,
+        trigger: SampleTrigger,
+        buffers: [&'static mut [i16]; 2],
+    ) -> SaadcContinuous {
+        for buffer in buffers.iter() {
+            // RESULT.MAXCNT is 15 bits wide.
+            assert!(!buffer.is_empty() && buffer.len() <= 0x7fff);
+            assert!(buffer.len() % self.channels == 0);
+        }
+
+        match trigger {
+            SampleTrigger::Internal(cc) => {
+                assert!(
+                    self.channels == 1,
+                    "the internal timer can only be used with a single channel"
+                );
+                self.saadc
+                    .samplerate
+                    .write(|w| unsafe { w.cc().bits(cc).mode().timers() });
+            }
+            SampleTrigger::Task => self.saadc.samplerate.write(|w| w.mode().task()),
+        }
+
+        self.saadc.events_started.reset();
+        self.saadc.events_end.reset();
+
+        set_result_buffer(&self.saadc, &buffers[0]);
+
+        // Conservative compiler fence to prevent starting the ADC before the
+        // pointer and maxcount have been set.
+        compiler_fence(SeqCst);
+
+        self.saadc.tasks_start.write(|w| unsafe { w.bits(1) });
+        while self.saadc.events_started.read().bits() == 0 {}
+        self.saadc.events_started.reset();
+
+        // The first buffer's pointer has been latched, so queue the second one
+        // for the START following the first END.
+        set_result_buffer(&self.saadc, &buffers[1]);
+
+        if let SampleTrigger::Internal(_) = trigger {
+            // The first SAMPLE task starts the internal timer.
+            self.saadc.tasks_sample.write(|w| unsafe { w.bits(1) });
+        }
+
+        SaadcContinuous {
+            inner: Some(ContinuousInner {
+                scan: self,
+                buffers,
+                active: 0,
+            }),
+        }
+    }
+
+    impl_limits!();
+
+    /// Return the raw interface to the underlying SAADC peripheral.
+    ///
+    /// The scan mode channel configuration remains in place.
+    pub fn free(self) -> SAADC {
+        self.saadc
+    }
+}
+
+fn set_result_buffer(saadc: &saadc::RegisterBlock, buffer: &[i16]) {
+    saadc
+        .result
+        .ptr
+        .write(|w| unsafe { w.ptr().bits(buffer.as_ptr() as u32) });
+    saadc
+        .result
+        .maxcnt
+        .write(|w| unsafe { w.maxcnt().bits(buffer.len() as _) });
+}
+
+/// What triggers the samples of continuous sampling.
+pub enum SampleTrigger {
+    /// The SAADC's internal timer samples at 16 MHz / `cc`, with `cc` in 80..=2047.
+    ///
+    /// Only possible when a single channel is configured.
+    Internal(u16),
+    /// A scan is sampled each time the SAMPLE task is triggered, e.g. through
+    /// PPI from a TIMER event, see [`SaadcContinuous::task_sample`].
+    Task,
+}
+
+/// Continuous double-buffered sampling, see [`SaadcScan::into_continuous`].
+///
+/// Dropping it stops sampling.
+pub struct SaadcContinuous {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<ContinuousInner>,
+}
+
+struct ContinuousInner {
+    scan: SaadcScan,
+    buffers: [&'static mut [i16]; 2],
+    active: usize,
+}
+
+impl SaadcContinuous {
+    #[inline(always)]
+    fn inner(&self) -> &ContinuousInner {
+        self.inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+    }
+
+    #[inline(always)]
+    fn regs(&self) -> &saadc::RegisterBlock {
+        &self.inner().scan.saadc
+    }
+
+    /// Enables the END and STARTED interrupts that drive [`SaadcContinuous::process`].
+    pub fn enable_interrupt(&mut self) {
+        self.regs()
+            .intenset
+            .write(|w| w.end().set().started().set());
+    }
+
+    /// Disables the END and STARTED interrupts.
+    pub fn disable_interrupt(&mut self) {
+        self.regs()
+            .intenclr
+            .write(|w| w.end().clear().started().clear());
+    }
+
+    impl_limits!();
+
+    /// Returns reference to the SAMPLE task endpoint for PPI.
+    #[inline(always)]
+    pub fn task_sample(&self) -> &saadc::TASKS_SAMPLE {
+        &self.regs().tasks_sample
+    }
+
+    /// Returns reference to the END event endpoint for PPI.
+    #[inline(always)]
+    pub fn event_end(&self) -> &saadc::EVENTS_END {
+        &self.regs().events_end
+    }
+
+    /// Handles pending END and STARTED events, calling `f` with a buffer that
+    /// has just been filled.
+    ///
+    /// Sampling continues into the other buffer right away, so `f` has until
+    /// that one is full to process the samples. Returns `true` if `f` was
+    /// called.
+    ///
+    /// Call this from the SAADC interrupt handler.
+    pub fn process<F>(&mut self, f: F) -> bool
+    where
+        F: FnOnce(&[i16]),
+    {
+        let inner = self
+            .inner
+            .as_mut()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        let saadc = &inner.scan.saadc;
+
+        if saadc.events_started.read().bits() != 0 {
+            saadc.events_started.reset();
+
+            // The buffer being filled has been latched, so queue the other one.
+            set_result_buffer(saadc, &inner.buffers[1 - inner.active]);
+        }
+
+        if saadc.events_end.read().bits() == 0 {
+            return false;
+        }
+        saadc.events_end.reset();
+
+        // Continue into the queued buffer.
+        saadc.tasks_start.write(|w| unsafe { w.bits(1) });
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions on the full buffer have completed.
+        compiler_fence(SeqCst);
+
+        let done = inner.active;
+        inner.active = 1 - inner.active;
+        f(&inner.buffers[done][..]);
+
+        true
+    }
+
+    /// Stops sampling and returns the scan mode SAADC and the buffers.
+    pub fn stop(mut self) -> (SaadcScan, [&'static mut [i16]; 2]) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        stop_continuous(&inner.scan.saadc);
+
+        (inner.scan, inner.buffers)
+    }
+}
+
+impl Drop for SaadcContinuous {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_ref() {
+            stop_continuous(&inner.scan.saadc);
+        }
+    }
+}
+
+/// Stops continuous sampling, leaving the SAADC ready for task-triggered scans.
+fn stop_continuous(saadc: &saadc::RegisterBlock) {
+    saadc.intenclr.write(|w| w.end().clear().started().clear());
+    saadc.samplerate.write(|w| w.mode().task());
+
+    saadc.events_stopped.reset();
+    saadc.tasks_stop.write(|w| unsafe { w.bits(1) });
+    while saadc.events_stopped.read().bits() == 0 {}
+    saadc.events_stopped.reset();
+    saadc.events_started.reset();
+    saadc.events_end.reset();
+
+    // Conservative compiler fence to prevent optimizations that do not
+    // take in to account actions by DMA. The fence has been placed here,
+    // after all possible DMA actions have completed.
+    compiler_fence(SeqCst);
 }
 
 /// Used to configure the SAADC peripheral.
 ///
 /// See the documentation of the `Default` impl for suitable default values.
+#[derive(Clone, Copy)]
 pub struct SaadcConfig {
     /// Output resolution in bits.
     pub resolution: Resolution,
@@ -159,6 +699,64 @@ impl Default for SaadcConfig {
     }
 }
 
+impl SaadcConfig {
+    /// Converts a raw result sampled with this configuration to millivolts.
+    ///
+    /// Keep a copy of the configuration passed to `Saadc::new` to convert
+    /// its results.
+    ///
+    /// `vdd_mv` is the supply voltage in millivolts, which is only used with
+    /// `Reference::VDD1_4`.
+    pub fn to_millivolts(&self, raw: i16, vdd_mv: u16) -> i32 {
+        to_millivolts(
+            raw,
+            &self.gain,
+            &self.reference,
+            &self.resolution,
+            false,
+            vdd_mv,
+        )
+    }
+}
+
+/// Converts a raw result to millivolts.
+///
+/// RESULT = (V(P) - V(N)) * GAIN / REFERENCE * 2^(RESOLUTION - m), with `m`
+/// being 1 in differential mode and 0 otherwise.
+fn to_millivolts(
+    raw: i16,
+    gain: &Gain,
+    reference: &Reference,
+    resolution: &Resolution,
+    differential: bool,
+    vdd_mv: u16,
+) -> i32 {
+    // Gain as numerator and denominator.
+    let (num, den) = match gain {
+        Gain::GAIN1_6 => (1, 6),
+        Gain::GAIN1_5 => (1, 5),
+        Gain::GAIN1_4 => (1, 4),
+        Gain::GAIN1_3 => (1, 3),
+        Gain::GAIN1_2 => (1, 2),
+        Gain::GAIN1 => (1, 1),
+        Gain::GAIN2 => (2, 1),
+        Gain::GAIN4 => (4, 1),
+    };
+    // Reference voltage, times 4 to keep VDD/4 exact.
+    let reference_mv4 = match reference {
+        Reference::INTERNAL => 600 * 4,
+        Reference::VDD1_4 => i64::from(vdd_mv),
+    };
+    let bits = match resolution {
+        Resolution::_8BIT => 8,
+        Resolution::_10BIT => 10,
+        Resolution::_12BIT => 12,
+        Resolution::_14BIT => 14,
+    } - differential as u32;
+
+    (i64::from(raw) * reference_mv4 * den / (num * 4 << bits)) as i32
+}
+
 impl<PIN> OneShot<Saadc, i16, PIN> for Saadc
 where
     PIN: Channel<Saadc, ID = u8>,
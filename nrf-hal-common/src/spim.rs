@@ -2,6 +2,7 @@
 //!
 //! See product specification, chapter 31.
 
+use core::cell::RefCell;
 use core::ops::Deref;
 use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
 
@@ -26,8 +27,9 @@ use crate::pac::{SPIM1, SPIM2};
 use crate::pac::SPIM3;
 
 use crate::gpio::{Floating, Input, Output, Pin, PushPull};
-use crate::target_constants::{EASY_DMA_SIZE, FORCE_COPY_BUFFER_SIZE};
+use crate::target_constants::{EASY_DMA_SIZE, FORCE_COPY_BUFFER_SIZE, SRAM_LOWER, SRAM_UPPER};
 use crate::{slice_in_ram, slice_in_ram_or, DmaSlice};
+use embedded_dma::{ReadBuffer, WriteBuffer};
 use embedded_hal::digital::v2::OutputPin;
 
 /// Interface to a SPIM instance.
@@ -122,8 +124,25 @@ where
         // Enable SPIM instance.
         spim.enable.write(|w| w.enable().enabled());
 
+        let mut spim = Spim(spim);
+        spim.set_mode(mode);
+        spim.set_frequency(frequency);
+
+        // Set over-read character to `0`.
+        spim.0.orc.write(|w|
+            // The ORC field is 8 bits long, so `0` is a valid value to write
+            // there.
+            unsafe { w.orc().bits(orc) });
+
+        spim
+    }
+
+    /// Set the SPI mode (clock polarity and phase).
+    ///
+    /// Must not be called while a transfer is in progress.
+    pub fn set_mode(&mut self, mode: Mode) {
         // Configure mode.
-        spim.config.write(|w| {
+        self.0.config.write(|w| {
             // Can't match on `mode` due to embedded-hal, see https://github.com/rust-embedded/embedded-hal/pull/126
             if mode == MODE_0 {
                 w.order().msb_first();
@@ -144,21 +163,30 @@ where
             }
             w
         });
+    }
 
-        // Configure frequency.
-        spim.frequency.write(|w| w.frequency().variant(frequency));
-
-        // Set over-read character to `0`.
-        spim.orc.write(|w|
-            // The ORC field is 8 bits long, so `0` is a valid value to write
-            // there.
-            unsafe { w.orc().bits(orc) });
-
-        Spim(spim)
+    /// Set the SPI clock frequency.
+    ///
+    /// Must not be called while a transfer is in progress.
+    pub fn set_frequency(&mut self, frequency: Frequency) {
+        self.0.frequency.write(|w| w.frequency().variant(frequency));
     }
 
     /// Internal helper function to setup and execute SPIM DMA transfer.
     fn do_spi_dma_transfer(&mut self, tx: DmaSlice, rx: DmaSlice) -> Result<(), Error> {
+        self.start_spi_dma_transfer(&tx, &rx);
+
+        // Wait for END event.
+        //
+        // This event is triggered once both transmitting and receiving are
+        // done.
+        while self.0.events_end.read().bits() == 0 {}
+
+        self.finish_spi_dma_transfer(&tx, &rx)
+    }
+
+    /// Internal helper function to setup and start a SPIM DMA transfer.
+    fn start_spi_dma_transfer(&mut self, tx: &DmaSlice, rx: &DmaSlice) {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
         // before any DMA action has started.
@@ -193,13 +221,10 @@ where
         // take in to account actions by DMA. The fence has been placed here,
         // after all possible DMA actions have completed.
         compiler_fence(SeqCst);
+    }
 
-        // Wait for END event.
-        //
-        // This event is triggered once both transmitting and receiving are
-        // done.
-        while self.0.events_end.read().bits() == 0 {}
-
+    /// Internal helper function to complete a SPIM DMA transfer after the END event.
+    fn finish_spi_dma_transfer(&mut self, tx: &DmaSlice, rx: &DmaSlice) -> Result<(), Error> {
         // Reset the event, otherwise it will always read `1` from now on.
         self.0.events_end.write(|w| w);
 
@@ -217,6 +242,43 @@ where
         Ok(())
     }
 
+    /// Internal helper to transmit `tx_buffer` and receive into `rx_buffer`,
+    /// without touching any chip select pin.
+    ///
+    /// The buffers may have different lengths, see `transfer_split_uneven`.
+    fn transfer_uneven(&mut self, tx_buffer: &[u8], rx_buffer: &mut [u8]) -> Result<(), Error> {
+        // For the tx and rx, we want to return Some(chunk)
+        // as long as there is data to send. We then chain a repeat to
+        // the end so once all chunks have been exhausted, we will keep
+        // getting Nones out of the iterators.
+        let txi = tx_buffer
+            .chunks(EASY_DMA_SIZE)
+            .map(Some)
+            .chain(repeat_with(|| None));
+
+        let rxi = rx_buffer
+            .chunks_mut(EASY_DMA_SIZE)
+            .map(Some)
+            .chain(repeat_with(|| None));
+
+        // We then chain the iterators together, and once BOTH are feeding
+        // back Nones, then we are done sending and receiving.
+        txi
+            .zip(rxi)
+            .take_while(|(t, r)| t.is_some() || r.is_some())
+            // We also turn the slices into either a DmaSlice (if there was data), or a null
+            // DmaSlice (if there is no data).
+            .map(|(t, r)| {
+                (
+                    t.map(|t| DmaSlice::from_slice(t))
+                        .unwrap_or_else(DmaSlice::null),
+                    r.map(|r| DmaSlice::from_slice(r))
+                        .unwrap_or_else(DmaSlice::null),
+                )
+            })
+            .try_for_each(|(t, r)| self.do_spi_dma_transfer(t, r))
+    }
+
     /// Read and write from a SPI slave, using a single buffer.
     ///
     /// This method implements a complete read transaction, which consists of
@@ -303,40 +365,10 @@ where
         // slice can only be built from data located in RAM.
         slice_in_ram_or(tx_buffer, Error::DMABufferNotInDataMemory)?;
 
-        // For the tx and rx, we want to return Some(chunk)
-        // as long as there is data to send. We then chain a repeat to
-        // the end so once all chunks have been exhausted, we will keep
-        // getting Nones out of the iterators.
-        let txi = tx_buffer
-            .chunks(EASY_DMA_SIZE)
-            .map(Some)
-            .chain(repeat_with(|| None));
-
-        let rxi = rx_buffer
-            .chunks_mut(EASY_DMA_SIZE)
-            .map(Some)
-            .chain(repeat_with(|| None));
-
         chip_select.set_low().unwrap();
 
-        // We then chain the iterators together, and once BOTH are feeding
-        // back Nones, then we are done sending and receiving.
-        //
         // Don't return early, as we must reset the CS pin.
-        let res = txi
-            .zip(rxi)
-            .take_while(|(t, r)| t.is_some() || r.is_some())
-            // We also turn the slices into either a DmaSlice (if there was data), or a null
-            // DmaSlice (if there is no data).
-            .map(|(t, r)| {
-                (
-                    t.map(|t| DmaSlice::from_slice(t))
-                        .unwrap_or_else(DmaSlice::null),
-                    r.map(|r| DmaSlice::from_slice(r))
-                        .unwrap_or_else(DmaSlice::null),
-                )
-            })
-            .try_for_each(|(t, r)| self.do_spi_dma_transfer(t, r));
+        let res = self.transfer_uneven(tx_buffer, rx_buffer);
 
         chip_select.set_high().unwrap();
 
@@ -363,6 +395,636 @@ where
     }
 }
 
+impl<T> Spim<T>
+where
+    T: Instance,
+{
+    /// Prepare an EasyDMA array-list reception of fixed-size records, without
+    /// starting it.
+    ///
+    /// Each time the START task is triggered, e.g. through PPI from a TIMER
+    /// event, `command` is transmitted while one record of `record_len` bytes is
+    /// received into the next slot of `records`. If `record_len` is longer than
+    /// `command`, the over-read character is transmitted for the remainder.
+    /// The CPU isn't involved between records.
+    ///
+    /// Chip select isn't driven by this peripheral, so it must be handled in
+    /// hardware too, e.g. by SPIM3's CSN line or by GPIOTE tasks on the same
+    /// PPI channels.
+    ///
+    /// # Safety
+    ///
+    /// EasyDMA advances through RAM with each record and doesn't stop at the end
+    /// of `records`. The START task must not be triggered more than
+    /// `records.len() / record_len` times before the transfer is stopped.
+    pub unsafe fn read_list<TX, RX>(
+        self,
+        command: TX,
+        mut records: RX,
+        record_len: usize,
+    ) -> Result<ListTransfer<T, TX, RX>, (Error, Self, TX, RX)>
+    where
+        TX: ReadBuffer<Word = u8> + 'static,
+        RX: WriteBuffer<Word = u8> + 'static,
+    {
+        let (tx_ptr, tx_len) = command.read_buffer();
+        let (rx_ptr, rx_len) = records.write_buffer();
+
+        if tx_len > EASY_DMA_SIZE {
+            return Err((Error::TxBufferTooLong, self, command, records));
+        }
+        if record_len == 0 || record_len > EASY_DMA_SIZE || record_len > rx_len {
+            return Err((Error::RxBufferTooLong, self, command, records));
+        }
+        if (tx_ptr as usize) < SRAM_LOWER || (tx_ptr as usize) > SRAM_UPPER {
+            return Err((Error::DMABufferNotInDataMemory, self, command, records));
+        }
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // before any DMA action has started.
+        compiler_fence(SeqCst);
+
+        // The command is sent from the same location for every record.
+        self.0.txd.list.write(|w| w.list().disabled());
+        self.0.txd.ptr.write(|w| w.ptr().bits(tx_ptr as u32));
+        self.0.txd.maxcnt.write(|w| w.maxcnt().bits(tx_len as _));
+
+        // Each END moves the receive pointer on by MAXCNT bytes.
+        self.0.rxd.list.write(|w| w.list().array_list());
+        self.0.rxd.ptr.write(|w| w.ptr().bits(rx_ptr as u32));
+        self.0.rxd.maxcnt.write(|w| w.maxcnt().bits(record_len as _));
+
+        self.0.events_end.reset();
+
+        Ok(ListTransfer {
+            inner: Some(ListInner {
+                spim: self,
+                command,
+                records,
+                rx_ptr: rx_ptr as u32,
+                record_len,
+            }),
+        })
+    }
+}
+
+/// An EasyDMA array-list reception, see [`Spim::read_list`].
+pub struct ListTransfer<T: Instance, TX, RX> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<ListInner<T, TX, RX>>,
+}
+
+struct ListInner<T, TX, RX> {
+    spim: Spim<T>,
+    command: TX,
+    records: RX,
+    rx_ptr: u32,
+    record_len: usize,
+}
+
+impl<T: Instance, TX, RX> ListTransfer<T, TX, RX> {
+    fn spim(&self) -> &T {
+        &self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+            .spim
+            .0
+    }
+
+    /// Returns reference to the START task endpoint for PPI, starting the next record.
+    #[inline(always)]
+    pub fn task_start(&self) -> &spim0::TASKS_START {
+        &self.spim().tasks_start
+    }
+
+    /// Returns reference to the END event endpoint for PPI, signalling a received record.
+    #[inline(always)]
+    pub fn event_end(&self) -> &spim0::EVENTS_END {
+        &self.spim().events_end
+    }
+
+    /// Returns the number of records received so far.
+    pub fn received(&self) -> usize {
+        let inner = self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        (self.spim().rxd.ptr.read().bits() - inner.rx_ptr) as usize / inner.record_len
+    }
+
+    /// Stops the reception and returns the SPIM and the buffers.
+    ///
+    /// Make sure the START task is no longer triggered before calling this.
+    pub fn stop(mut self) -> (Spim<T>, TX, RX) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        stop_list(&inner.spim.0);
+        (inner.spim, inner.command, inner.records)
+    }
+}
+
+impl<T: Instance, TX, RX> Drop for ListTransfer<T, TX, RX> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_ref() {
+            stop_list(&inner.spim.0);
+        }
+    }
+}
+
+/// Ends an array-list reception, aborting a record in progress.
+fn stop_list(spim: &spim0::RegisterBlock) {
+    // Disabling the instance ends any ongoing transfer immediately.
+    spim.enable.write(|w| w.enable().disabled());
+    spim.rxd.list.write(|w| w.list().disabled());
+    spim.events_end.reset();
+    spim.events_started.reset();
+    spim.events_stopped.reset();
+    spim.enable.write(|w| w.enable().enabled());
+
+    // Conservative compiler fence to prevent optimizations that do not
+    // take in to account actions by DMA. The fence has been placed here,
+    // after all possible DMA actions have completed.
+    compiler_fence(SeqCst);
+}
+
+/// A device on a SPIM bus that is shared with other devices.
+///
+/// Each device owns its chip select pin and has its own frequency and mode.
+/// The bus is reconfigured accordingly at the start of each transaction, so
+/// several devices can take turns on the same `Spim`:
+///
+/// ```ignore
+/// let bus = RefCell::new(Spim::new(p.SPIM2, pins, Frequency::M8, MODE_0, 0));
+/// let mut flash = SpimDevice::new_no_delay(&bus, flash_cs, Frequency::M8, MODE_0);
+/// let mut imu = SpimDevice::new(&bus, imu_cs, Frequency::M1, MODE_3, Delay::new(cp.SYST));
+/// ```
+///
+/// The `delay` is used for the `Operation::DelayNs` steps of embedded-hal 1.0
+/// transactions.
+///
+/// # Panics
+///
+/// A transaction panics if the bus is already borrowed, e.g. when a device
+/// is used from an interrupt handler while another device on the same bus is
+/// in a transaction.
+pub struct SpimDevice<'a, T, D = NoDelay> {
+    bus: &'a RefCell<Spim<T>>,
+    chip_select: Pin<Output<PushPull>>,
+    frequency: Frequency,
+    mode: Mode,
+    delay: D,
+}
+
+impl<'a, T> SpimDevice<'a, T, NoDelay>
+where
+    T: Instance,
+{
+    /// Creates a new device on `bus`, selected by `chip_select`, that doesn't
+    /// support `Operation::DelayNs`.
+    ///
+    /// The chip select pin is driven high (deselected) immediately.
+    pub fn new_no_delay(
+        bus: &'a RefCell<Spim<T>>,
+        chip_select: Pin<Output<PushPull>>,
+        frequency: Frequency,
+        mode: Mode,
+    ) -> Self {
+        Self::new(bus, chip_select, frequency, mode, NoDelay)
+    }
+}
+
+impl<'a, T, D> SpimDevice<'a, T, D>
+where
+    T: Instance,
+{
+    /// Creates a new device on `bus`, selected by `chip_select`.
+    ///
+    /// The chip select pin is driven high (deselected) immediately.
+    pub fn new(
+        bus: &'a RefCell<Spim<T>>,
+        mut chip_select: Pin<Output<PushPull>>,
+        frequency: Frequency,
+        mode: Mode,
+        delay: D,
+    ) -> Self {
+        chip_select.set_high().unwrap();
+        Self {
+            bus,
+            chip_select,
+            frequency,
+            mode,
+            delay,
+        }
+    }
+
+    /// Runs `f` on the bus, configured for this device and with the device
+    /// selected.
+    fn with_bus<R>(&mut self, f: impl FnOnce(&mut Spim<T>, &mut D) -> R) -> R {
+        let mut spim = self.bus.borrow_mut();
+        spim.set_frequency(self.frequency);
+        spim.set_mode(self.mode);
+
+        self.chip_select.set_low().unwrap();
+        let res = f(&mut spim, &mut self.delay);
+        self.chip_select.set_high().unwrap();
+
+        res
+    }
+
+    /// Read and write from the device, using a single buffer.
+    ///
+    /// See [`Spim::transfer`].
+    pub fn transfer(&mut self, buffer: &mut [u8]) -> Result<(), Error> {
+        self.with_bus(|spim, _| {
+            embedded_hal::blocking::spi::Transfer::transfer(spim, buffer).map(|_| ())
+        })
+    }
+
+    /// Read and write from the device, using separate read and write buffers
+    /// of possibly different lengths.
+    ///
+    /// See [`Spim::transfer_split_uneven`].
+    pub fn transfer_split_uneven(
+        &mut self,
+        tx_buffer: &[u8],
+        rx_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        slice_in_ram_or(tx_buffer, Error::DMABufferNotInDataMemory)?;
+        self.with_bus(|spim, _| spim.transfer_uneven(tx_buffer, rx_buffer))
+    }
+
+    /// Write to the device, discarding all incoming bytes.
+    pub fn write(&mut self, tx_buffer: &[u8]) -> Result<(), Error> {
+        self.with_bus(|spim, _| embedded_hal::blocking::spi::Write::write(spim, tx_buffer))
+    }
+
+    /// Returns the chip select pin and the delay.
+    pub fn free(self) -> (Pin<Output<PushPull>>, D) {
+        (self.chip_select, self.delay)
+    }
+}
+
+impl<T, D> embedded_hal::blocking::spi::Transfer<u8> for SpimDevice<'_, T, D>
+where
+    T: Instance,
+{
+    type Error = Error;
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Error> {
+        SpimDevice::transfer(self, words)?;
+        Ok(words)
+    }
+}
+
+impl<T, D> embedded_hal::blocking::spi::Write<u8> for SpimDevice<'_, T, D>
+where
+    T: Instance,
+{
+    type Error = Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        SpimDevice::write(self, words)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Spim<T>
+where
+    T: Instance,
+{
+    /// Handles the SPIM interrupt for the async API.
+    ///
+    /// This must be called from the interrupt handler of instance `T` when using the
+    /// `embedded-hal-async` traits, and the interrupt must be unmasked in the NVIC.
+    pub fn on_interrupt() {
+        let spim = unsafe { &*T::ptr() };
+
+        // The awaiting future checks the event itself and re-enables the interrupt as needed.
+        spim.intenclr.write(|w| w.end().clear());
+        T::waker().wake();
+    }
+
+    /// Internal helper function to execute a SPIM DMA transfer without blocking.
+    async fn do_spi_dma_transfer_async(&mut self, tx: DmaSlice, rx: DmaSlice) -> Result<(), Error> {
+        self.start_spi_dma_transfer(&tx, &rx);
+
+        // Stop the transfer if the future is dropped before it completes, so the
+        // DMA doesn't keep accessing buffers that are about to go out of scope.
+        let on_drop = crate::asynch::OnDrop::new(|| {
+            let spim = unsafe { &*T::ptr() };
+            spim.intenclr.write(|w| w.end().clear());
+            if spim.events_end.read().bits() == 0 {
+                spim.tasks_stop.write(|w| unsafe { w.bits(1) });
+                while spim.events_stopped.read().bits() == 0 && spim.events_end.read().bits() == 0
+                {
+                }
+            }
+            spim.events_stopped.reset();
+            spim.events_end.reset();
+        });
+
+        core::future::poll_fn(|cx| {
+            T::waker().register(cx.waker());
+
+            if self.0.events_end.read().bits() != 0 {
+                return core::task::Poll::Ready(());
+            }
+
+            self.0.intenset.write(|w| w.end().set());
+            core::task::Poll::Pending
+        })
+        .await;
+
+        on_drop.defuse();
+        self.finish_spi_dma_transfer(&tx, &rx)
+    }
+
+    /// Async counterpart of `transfer_uneven`.
+    async fn transfer_uneven_async(
+        &mut self,
+        tx_buffer: &[u8],
+        rx_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut txi = tx_buffer.chunks(EASY_DMA_SIZE);
+        let mut rxi = rx_buffer.chunks_mut(EASY_DMA_SIZE);
+
+        loop {
+            let (t, r) = match (txi.next(), rxi.next()) {
+                (None, None) => return Ok(()),
+                (t, r) => (
+                    t.map(DmaSlice::from_slice).unwrap_or_else(DmaSlice::null),
+                    r.map(|r| DmaSlice::from_slice(r))
+                        .unwrap_or_else(DmaSlice::null),
+                ),
+            };
+            self.do_spi_dma_transfer_async(t, r).await?;
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> embedded_hal_async::spi::SpiBus<u8> for Spim<T>
+where
+    T: Instance,
+{
+    async fn read(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        // The `orc` character is clocked out while reading.
+        self.transfer_uneven_async(&[], words).await
+    }
+
+    async fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        if slice_in_ram(words) {
+            return self.transfer_uneven_async(words, &mut []).await;
+        }
+
+        // EasyDMA can't read from flash, so copy the data to the stack chunk by chunk.
+        let mut buf = [0u8; FORCE_COPY_BUFFER_SIZE];
+        for chunk in words.chunks(FORCE_COPY_BUFFER_SIZE) {
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.do_spi_dma_transfer_async(
+                DmaSlice::from_slice(&buf[..chunk.len()]),
+                DmaSlice::null(),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Error> {
+        slice_in_ram_or(write, Error::DMABufferNotInDataMemory)?;
+        self.transfer_uneven_async(write, read).await
+    }
+
+    async fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        for chunk in words.chunks_mut(EASY_DMA_SIZE) {
+            self.do_spi_dma_transfer_async(DmaSlice::from_slice(chunk), DmaSlice::from_slice(chunk))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        // Each transfer is awaited until completion, so there is never anything in flight.
+        Ok(())
+    }
+}
+
+/// Clock frequency of SPIM3, which also supports 16 and 32 MHz.
+#[cfg(any(feature = "52833", feature = "52840"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Spim3Frequency {
+    K125,
+    K250,
+    K500,
+    M1,
+    M2,
+    M4,
+    M8,
+    M16,
+    M32,
+}
+
+#[cfg(any(feature = "52833", feature = "52840"))]
+impl Spim3Frequency {
+    fn bits(self) -> u32 {
+        match self {
+            Spim3Frequency::K125 => 0x0200_0000,
+            Spim3Frequency::K250 => 0x0400_0000,
+            Spim3Frequency::K500 => 0x0800_0000,
+            Spim3Frequency::M1 => 0x1000_0000,
+            Spim3Frequency::M2 => 0x2000_0000,
+            Spim3Frequency::M4 => 0x4000_0000,
+            Spim3Frequency::M8 => 0x8000_0000,
+            Spim3Frequency::M16 => 0x0A00_0000,
+            Spim3Frequency::M32 => 0x1400_0000,
+        }
+    }
+}
+
+/// Active level of the hardware-controlled chip select line of SPIM3.
+#[cfg(any(feature = "52833", feature = "52840"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsnPolarity {
+    ActiveLow,
+    ActiveHigh,
+}
+
+/// Configuration of the features only available on SPIM3.
+#[cfg(any(feature = "52833", feature = "52840"))]
+pub struct Spim3Config {
+    /// SPI clock frequency.
+    pub frequency: Spim3Frequency,
+
+    /// Chip select line, driven by the peripheral for the duration of each transfer.
+    /// None if unused
+    pub csn: Option<Pin<Output<PushPull>>>,
+
+    /// Active level of `csn`.
+    pub csn_polarity: CsnPolarity,
+
+    /// Minimum time between the edges of CSN and SCK, and between transfers, in
+    /// 64 MHz periods (15.625 ns).
+    pub csn_duration: u8,
+
+    /// Data/command line for displays, see [`Spim::set_dcx_count`].
+    /// None if unused
+    pub dcx: Option<Pin<Output<PushPull>>>,
+
+    /// Delay of the MISO sampling point after the sampling edge of SCK, in
+    /// 64 MHz periods (15.625 ns). At most 7.
+    pub rx_delay: u8,
+}
+
+#[cfg(any(feature = "52833", feature = "52840"))]
+impl Default for Spim3Config {
+    /// 8 MHz without hardware chip select or data/command line, and the reset
+    /// values for the timings.
+    fn default() -> Self {
+        Self {
+            frequency: Spim3Frequency::M8,
+            csn: None,
+            csn_polarity: CsnPolarity::ActiveLow,
+            csn_duration: 2,
+            dcx: None,
+            rx_delay: 2,
+        }
+    }
+}
+
+#[cfg(any(feature = "52833", feature = "52840"))]
+impl Spim<SPIM3> {
+    /// Create a SPIM3 instance, using its high-speed features.
+    ///
+    /// When `config.csn` is set, the peripheral drives chip select itself, so
+    /// the bus is best used through the `embedded-hal` traits rather than the
+    /// methods taking a chip select pin.
+    ///
+    /// Use [`Spim::free_spim3`] to get the CSN and D/CX pins back.
+    ///
+    /// # Buffer placement
+    ///
+    /// On the nRF52840, data sent by SPIM3 can be corrupted when its EasyDMA
+    /// reads the transmit buffer from a RAM AHB slave that the CPU or another
+    /// EasyDMA peripheral accesses at the same time (anomaly 198). Keep the
+    /// transmit buffers in a RAM region of their own, e.g. through a dedicated
+    /// linker section, to avoid this.
+    pub fn new_spim3(spim: SPIM3, pins: Pins, mode: Mode, orc: u8, config: Spim3Config) -> Self {
+        let mut spim = Self::new(spim, pins, Frequency::M8, mode, orc);
+
+        // Pins must only be selected while the instance is disabled.
+        disable_spim3(&spim.0);
+
+        match config.csn {
+            Some(csn) => spim.0.psel.csn.write(|w| {
+                unsafe { w.bits(csn.psel_bits()) };
+                w.connect().connected()
+            }),
+            None => spim.0.psel.csn.write(|w| w.connect().disconnected()),
+        }
+        match config.dcx {
+            Some(dcx) => spim.0.pseldcx.write(|w| {
+                unsafe { w.bits(dcx.psel_bits()) };
+                w.connect().connected()
+            }),
+            None => spim.0.pseldcx.write(|w| w.connect().disconnected()),
+        }
+
+        spim.0.csnpol.write(|w| match config.csn_polarity {
+            CsnPolarity::ActiveLow => w.csnpol().low(),
+            CsnPolarity::ActiveHigh => w.csnpol().high(),
+        });
+        spim.0
+            .iftiming
+            .csndur
+            .write(|w| unsafe { w.csndur().bits(config.csn_duration) });
+        spim.0
+            .iftiming
+            .rxdelay
+            .write(|w| unsafe { w.rxdelay().bits(config.rx_delay.min(7)) });
+
+        // The field accepts the 16 and 32 MHz values, which the SVD variants of the
+        // other instances lack.
+        spim.0
+            .frequency
+            .write(|w| unsafe { w.bits(config.frequency.bits()) });
+
+        spim.0.enable.write(|w| w.enable().enabled());
+
+        spim
+    }
+
+    /// Return the raw interface to SPIM3, along with the CSN and D/CX pins passed
+    /// in the configuration, if any.
+    ///
+    /// The pins are disconnected from the peripheral, which is left disabled.
+    #[allow(clippy::type_complexity)]
+    pub fn free_spim3(
+        self,
+    ) -> (
+        SPIM3,
+        Option<Pin<Output<PushPull>>>,
+        Option<Pin<Output<PushPull>>>,
+    ) {
+        // Pins must only be selected while the instance is disabled.
+        disable_spim3(&self.0);
+
+        // NOTE(unsafe) the pins were handed to the peripheral in `new_spim3`, and
+        // it stops using them once disconnected.
+        let take = |bits: u32| {
+            if bits & (1 << 31) == 0 {
+                Some(unsafe { Pin::from_psel_bits(bits) })
+            } else {
+                None
+            }
+        };
+        let csn = take(self.0.psel.csn.read().bits());
+        let dcx = take(self.0.pseldcx.read().bits());
+        self.0.psel.csn.write(|w| w.connect().disconnected());
+        self.0.pseldcx.write(|w| w.connect().disconnected());
+
+        (self.0, csn, dcx)
+    }
+
+    /// Set the SPI clock frequency, including the rates only SPIM3 supports.
+    ///
+    /// Must not be called while a transfer is in progress.
+    pub fn set_spim3_frequency(&mut self, frequency: Spim3Frequency) {
+        self.0
+            .frequency
+            .write(|w| unsafe { w.bits(frequency.bits()) });
+    }
+
+    /// Set the number of command bytes at the start of each transfer.
+    ///
+    /// The D/CX line is held low while the first `count` bytes are sent, and
+    /// high for the rest of the transfer. A `count` of 15 marks all bytes as
+    /// command bytes.
+    pub fn set_dcx_count(&mut self, count: u8) {
+        self.0
+            .dcxcnt
+            .write(|w| unsafe { w.dcxcnt().bits(count.min(15)) });
+    }
+}
+
+/// Disable SPIM3.
+///
+/// On the nRF52840, SPIM3 keeps drawing current once disabled unless it is
+/// also powered down through an undocumented register (anomaly 195).
+#[cfg(any(feature = "52833", feature = "52840"))]
+fn disable_spim3(spim: &SPIM3) {
+    spim.enable.write(|w| w.enable().disabled());
+
+    // NOTE(unsafe) workaround taken from the nRF52840 errata.
+    #[cfg(feature = "52840")]
+    unsafe {
+        core::ptr::write_volatile(0x4002_F004 as *mut u32, 1);
+    }
+}
+
 /// GPIO pins for SPIM interface
 pub struct Pins {
     /// SPI clock
@@ -387,15 +1049,232 @@ pub enum Error {
     Receive,
 }
 
+#[cfg(feature = "embedded-hal-1")]
+impl eh1::spi::Error for Error {
+    fn kind(&self) -> eh1::spi::ErrorKind {
+        eh1::spi::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T> eh1::spi::ErrorType for Spim<T>
+where
+    T: Instance,
+{
+    type Error = Error;
+}
+
+/// The bus is used without a chip select line; see [`ExclusiveDevice`] for a
+/// `SpiDevice` that manages one.
+#[cfg(feature = "embedded-hal-1")]
+impl<T> eh1::spi::SpiBus<u8> for Spim<T>
+where
+    T: Instance,
+{
+    fn read(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        // The `orc` character is clocked out while reading.
+        self.transfer_uneven(&[], words)
+    }
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Error> {
+        embedded_hal::blocking::spi::Write::write(self, words)
+    }
+
+    fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Error> {
+        slice_in_ram_or(write, Error::DMABufferNotInDataMemory)?;
+        self.transfer_uneven(write, read)
+    }
+
+    fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        embedded_hal::blocking::spi::Transfer::transfer(self, words).map(|_| ())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        // All transfers are blocking, so there is never anything in flight.
+        Ok(())
+    }
+}
+
+/// A SPIM instance with a single device attached, selected by a dedicated
+/// chip select pin.
+///
+/// Implements the embedded-hal 1.0 `SpiDevice` trait, driving the chip select
+/// pin low for the duration of each transaction. The `delay` is used for the
+/// `Operation::DelayNs` steps of a transaction.
+#[cfg(feature = "embedded-hal-1")]
+pub struct ExclusiveDevice<T, D = NoDelay> {
+    spim: Spim<T>,
+    chip_select: Pin<Output<PushPull>>,
+    delay: D,
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T> ExclusiveDevice<T, NoDelay>
+where
+    T: Instance,
+{
+    /// Creates a new device from a SPIM instance and its chip select pin,
+    /// that doesn't support `Operation::DelayNs`.
+    ///
+    /// The chip select pin is driven high (deselected) immediately.
+    pub fn new_no_delay(spim: Spim<T>, chip_select: Pin<Output<PushPull>>) -> Self {
+        Self::new(spim, chip_select, NoDelay)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T, D> ExclusiveDevice<T, D>
+where
+    T: Instance,
+{
+    /// Creates a new device from a SPIM instance, its chip select pin and a
+    /// delay provider.
+    ///
+    /// The chip select pin is driven high (deselected) immediately.
+    pub fn new(spim: Spim<T>, mut chip_select: Pin<Output<PushPull>>, delay: D) -> Self {
+        chip_select.set_high().unwrap();
+        Self {
+            spim,
+            chip_select,
+            delay,
+        }
+    }
+
+    /// Returns the SPIM instance, chip select pin and delay.
+    pub fn free(self) -> (Spim<T>, Pin<Output<PushPull>>, D) {
+        (self.spim, self.chip_select, self.delay)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T, D> eh1::spi::ErrorType for ExclusiveDevice<T, D>
+where
+    T: Instance,
+{
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T, D> eh1::spi::SpiDevice<u8> for ExclusiveDevice<T, D>
+where
+    T: Instance,
+    D: eh1::delay::DelayNs,
+{
+    fn transaction(
+        &mut self,
+        operations: &mut [eh1::spi::Operation<'_, u8>],
+    ) -> Result<(), Error> {
+        self.chip_select.set_low().unwrap();
+
+        // Don't return early, as we must reset the CS pin.
+        let res = operations
+            .iter_mut()
+            .try_for_each(|op| do_operation(&mut self.spim, &mut self.delay, op));
+
+        self.chip_select.set_high().unwrap();
+
+        res
+    }
+}
+
+/// Perform a single operation of an embedded-hal 1.0 `SpiDevice` transaction.
+#[cfg(feature = "embedded-hal-1")]
+fn do_operation<T, D>(
+    spim: &mut Spim<T>,
+    delay: &mut D,
+    operation: &mut eh1::spi::Operation<'_, u8>,
+) -> Result<(), Error>
+where
+    T: Instance,
+    D: eh1::delay::DelayNs,
+{
+    use eh1::spi::{Operation, SpiBus};
+
+    match operation {
+        Operation::Read(words) => spim.read(words),
+        Operation::Write(words) => SpiBus::write(spim, words),
+        Operation::Transfer(read, write) => SpiBus::transfer(spim, read, write),
+        Operation::TransferInPlace(words) => spim.transfer_in_place(words),
+        Operation::DelayNs(ns) => {
+            delay.delay_ns(*ns);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T, D> eh1::spi::ErrorType for SpimDevice<'_, T, D>
+where
+    T: Instance,
+{
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T, D> eh1::spi::SpiDevice<u8> for SpimDevice<'_, T, D>
+where
+    T: Instance,
+    D: eh1::delay::DelayNs,
+{
+    fn transaction(
+        &mut self,
+        operations: &mut [eh1::spi::Operation<'_, u8>],
+    ) -> Result<(), Error> {
+        self.with_bus(|spim, delay| {
+            operations
+                .iter_mut()
+                .try_for_each(|op| do_operation(spim, delay, op))
+        })
+    }
+}
+
+/// A placeholder delay for devices whose transactions don't contain
+/// `Operation::DelayNs`.
+///
+/// # Panics
+///
+/// Panics when used, i.e. when a transaction does contain a delay.
+pub struct NoDelay;
+
+#[cfg(feature = "embedded-hal-1")]
+impl eh1::delay::DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {
+        panic!("`Operation::DelayNs` requires a device created with a delay provider");
+    }
+}
+
 /// Implemented by all SPIM instances.
-pub trait Instance: Deref<Target = spim0::RegisterBlock> + sealed::Sealed {}
+pub trait Instance: Deref<Target = spim0::RegisterBlock> + sealed::Sealed {
+    /// Returns a pointer to the instance's register block.
+    fn ptr() -> *const spim0::RegisterBlock;
+}
 
 mod sealed {
-    pub trait Sealed {}
+    pub trait Sealed {
+        #[cfg(feature = "async")]
+        fn waker() -> &'static crate::asynch::AtomicWaker;
+    }
+}
+
+macro_rules! impl_instance {
+    ($SPIMx:ident) => {
+        impl sealed::Sealed for $SPIMx {
+            #[cfg(feature = "async")]
+            fn waker() -> &'static crate::asynch::AtomicWaker {
+                static WAKER: crate::asynch::AtomicWaker = crate::asynch::AtomicWaker::new();
+                &WAKER
+            }
+        }
+
+        impl Instance for $SPIMx {
+            fn ptr() -> *const spim0::RegisterBlock {
+                $SPIMx::ptr()
+            }
+        }
+    };
 }
 
-impl sealed::Sealed for SPIM0 {}
-impl Instance for SPIM0 {}
+impl_instance!(SPIM0);
 
 #[cfg(any(
     feature = "52832",
@@ -405,20 +1284,17 @@ impl Instance for SPIM0 {}
 ))]
 mod _spim1 {
     use super::*;
-    impl Instance for SPIM1 {}
-    impl sealed::Sealed for SPIM1 {}
+    impl_instance!(SPIM1);
 }
 
 #[cfg(any(feature = "52832", feature = "52833", feature = "52840"))]
 mod _spim2 {
     use super::*;
-    impl Instance for SPIM2 {}
-    impl sealed::Sealed for SPIM2 {}
+    impl_instance!(SPIM2);
 }
 
 #[cfg(any(feature = "52833", feature = "52840"))]
 mod _spim3 {
     use super::*;
-    impl Instance for SPIM3 {}
-    impl sealed::Sealed for SPIM3 {}
+    impl_instance!(SPIM3);
 }
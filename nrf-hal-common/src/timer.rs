@@ -345,6 +345,21 @@ where
     }
 }
 
+#[cfg(feature = "embedded-hal-1")]
+impl<T, U> eh1::delay::DelayNs for Timer<T, U>
+where
+    T: Instance,
+{
+    fn delay_ns(&mut self, ns: u32) {
+        // The timer runs at 1 MHz, so round up to whole microseconds.
+        self.delay(ns / 1_000 + u32::from(ns % 1_000 != 0))
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.delay(us)
+    }
+}
+
 /// Implemented by all TIMER* instances.
 pub trait Instance: sealed::Sealed {
     /// This interrupt associated with this RTC instance.
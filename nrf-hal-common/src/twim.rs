@@ -19,10 +19,15 @@ use crate::pac::{twim0, TWIM0};
 use crate::pac::TWIM1;
 
 use crate::{
-    gpio::{Floating, Input, Pin},
+    gpio::{Floating, Input, OpenDrain, Output, Pin},
     slice_in_ram, slice_in_ram_or,
-    target_constants::{EASY_DMA_SIZE, FORCE_COPY_BUFFER_SIZE},
+    target_constants::{EASY_DMA_SIZE, FORCE_COPY_BUFFER_SIZE, SRAM_LOWER, SRAM_UPPER},
+    timer::{self, Timer},
 };
+use embedded_dma::{ReadBuffer, WriteBuffer};
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use embedded_hal::timer::CountDown;
 
 pub use twim0::frequency::FREQUENCY_A as Frequency;
 
@@ -50,20 +55,8 @@ where
         // the pins through the raw peripheral API. All of the following is
         // safe, as we own the pins now and have exclusive access to their
         // registers.
-        for &pin in &[&pins.scl, &pins.sda] {
-            pin.conf().write(|w| {
-                w.dir()
-                    .input()
-                    .input()
-                    .connect()
-                    .pull()
-                    .pullup()
-                    .drive()
-                    .s0d1()
-                    .sense()
-                    .disabled()
-            });
-        }
+        configure_pin(&pins.scl);
+        configure_pin(&pins.sda);
 
         // Select pins.
         twim.psel.scl.write(|w| {
@@ -181,30 +174,103 @@ where
             return Err(Error::DataNack);
         }
         if err.overrun().is_received() {
-            return Err(Error::DataNack);
+            return Err(Error::Overrun);
         }
         Ok(())
     }
 
-    /// Wait for stop or error
+    /// Wait for stop or error of a transfer.
     fn wait(&mut self) {
+        // This can't time out, so only returns once the transfer has stopped.
+        let _ = self.wait_until(|| false);
+    }
+
+    /// Wait for stop, suspend or error, giving up once `timed_out` returns
+    /// `true`.
+    ///
+    /// On timeout, the transfer is aborted and `Error::Timeout` is returned.
+    fn wait_until(&mut self, mut timed_out: impl FnMut() -> bool) -> Result<bool, Error> {
         loop {
             if self.0.events_stopped.read().bits() != 0 {
                 self.0.events_stopped.reset();
-                break;
+                return Ok(false);
+            }
+            if self.0.events_suspended.read().bits() != 0 {
+                self.0.events_suspended.reset();
+                return Ok(true);
             }
             if self.0.events_error.read().bits() != 0 {
                 self.0.events_error.reset();
+                // The bus may be suspended, in which case STOP only takes
+                // effect once it is resumed.
+                self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
                 self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
             }
+            if timed_out() {
+                self.abort();
+                return Err(Error::Timeout);
+            }
         }
     }
 
-    /// Write to an I2C slave.
+    /// Abort an ongoing transfer.
     ///
-    /// The buffer must have a length of at most 255 bytes on the nRF52832
-    /// and at most 65535 bytes on the nRF52840.
-    pub fn write(&mut self, address: u8, buffer: &[u8]) -> Result<(), Error> {
+    /// The instance is disabled and re-enabled right away, without waiting for
+    /// STOPPED and without checking the state of the bus: a slave that holds
+    /// the bus keeps holding it. Use [`Twim::recover_bus`] to release it.
+    fn abort(&mut self) {
+        self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+        self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+
+        // A slave holding the bus can keep the STOP from ever completing, so
+        // disable the instance to end the transfer regardless.
+        self.0.enable.write(|w| w.enable().disabled());
+        self.0.enable.write(|w| w.enable().enabled());
+
+        self.0.events_stopped.reset();
+        self.0.events_suspended.reset();
+        self.0.events_error.reset();
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed.
+        compiler_fence(SeqCst);
+    }
+
+    /// Stop a suspended transaction, generating a stop condition.
+    fn stop_suspended(&mut self) {
+        stop_bounded(&self.0);
+    }
+
+    /// Check the outcome of a finished transfer.
+    ///
+    /// `tx_len` and `rx_len` are the expected byte counts of the transfer's
+    /// write and read parts, if any.
+    fn finish(&mut self, tx_len: Option<usize>, rx_len: Option<usize>) -> Result<(), Error> {
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed.
+        compiler_fence(SeqCst);
+
+        self.read_errorsrc()?;
+
+        if let Some(len) = tx_len {
+            if self.0.txd.amount.read().bits() != len as u32 {
+                return Err(Error::Transmit);
+            }
+        }
+
+        if let Some(len) = rx_len {
+            if self.0.rxd.amount.read().bits() != len as u32 {
+                return Err(Error::Receive);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Set up and start a write to an I2C slave.
+    fn start_write(&mut self, address: u8, buffer: &[u8]) -> Result<(), Error> {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
         // before any DMA action has started.
@@ -229,27 +295,11 @@ where
             // `1` is a valid value to write to task registers.
             unsafe { w.bits(1) });
 
-        self.wait();
-
-        // Conservative compiler fence to prevent optimizations that do not
-        // take in to account actions by DMA. The fence has been placed here,
-        // after all possible DMA actions have completed.
-        compiler_fence(SeqCst);
-
-        self.read_errorsrc()?;
-
-        if self.0.txd.amount.read().bits() != buffer.len() as u32 {
-            return Err(Error::Transmit);
-        }
-
         Ok(())
     }
 
-    /// Read from an I2C slave.
-    ///
-    /// The buffer must have a length of at most 255 bytes on the nRF52832
-    /// and at most 65535 bytes on the nRF52840.
-    pub fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
+    /// Set up and start a read from an I2C slave.
+    fn start_read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
         // before any DMA action has started.
@@ -273,28 +323,12 @@ where
             // `1` is a valid value to write to task registers.
             unsafe { w.bits(1) });
 
-        self.wait();
-
-        // Conservative compiler fence to prevent optimizations that do not
-        // take in to account actions by DMA. The fence has been placed here,
-        // after all possible DMA actions have completed.
-        compiler_fence(SeqCst);
-
-        self.read_errorsrc()?;
-
-        if self.0.rxd.amount.read().bits() != buffer.len() as u32 {
-            return Err(Error::Receive);
-        }
-
         Ok(())
     }
 
-    /// Write data to an I2C slave, then read data from the slave without
-    /// triggering a stop condition between the two.
-    ///
-    /// The buffers must have a length of at most 255 bytes on the nRF52832
-    /// and at most 65535 bytes on the nRF52840.
-    pub fn write_then_read(
+    /// Set up and start a write followed by a read, with a repeated start in
+    /// between.
+    fn start_write_then_read(
         &mut self,
         address: u8,
         wr_buffer: &[u8],
@@ -329,29 +363,343 @@ where
         // `1` is a valid value to write to task registers.
         self.0.tasks_starttx.write(|w| unsafe { w.bits(1) });
 
-        self.wait();
+        Ok(())
+    }
 
+    /// Set up and start one segment of a transaction, resuming the bus if it
+    /// was suspended by the previous segment.
+    ///
+    /// Merged reads are received into `copy`, and merged writes or a write
+    /// buffer not located in RAM are copied into it, see `Segment::copy_lens`.
+    /// Returns the expected write and read byte counts of the segment.
+    fn start_segment<O: AsOperation>(
+        &mut self,
+        operations: &mut [O],
+        segment: &Segment,
+        resume: bool,
+        copy: &mut [u8; FORCE_COPY_BUFFER_SIZE],
+    ) -> Result<(Option<usize>, Option<usize>), Error> {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
-        // after all possible DMA actions have completed.
+        // before any DMA action has started.
         compiler_fence(SeqCst);
 
-        self.read_errorsrc()?;
+        let starts_with_read = operations[0].is_read();
+        let (tx_copy_len, rx_copy_len) = segment.copy_lens(operations);
+        let (first, second) = operations.split_at_mut(segment.first);
+        let (writes, reads) = if starts_with_read {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        let mut tx_len = None;
+        let mut rx_len = None;
+
+        // Set up DMA buffers. Merged reads are received at the start of `copy`,
+        // followed by the copied writes.
+        let (rx_copy, tx_copy) = copy.split_at_mut(rx_copy_len);
+        if !writes.is_empty() {
+            let buffer = if tx_copy_len == 0 {
+                writes[0].bytes()
+            } else {
+                let mut len = 0;
+                for write in writes.iter() {
+                    let bytes = write.bytes();
+                    tx_copy[len..len + bytes.len()].copy_from_slice(bytes);
+                    len += bytes.len();
+                }
+                &tx_copy[..len]
+            };
+            unsafe { self.set_tx_buffer(buffer)? };
+            tx_len = Some(buffer.len());
+        }
+        if rx_copy_len != 0 {
+            unsafe { self.set_rx_buffer(rx_copy)? };
+            rx_len = Some(rx_copy_len);
+        } else {
+            // At most one read, received in place.
+            for read in reads.iter_mut() {
+                if let Operation::Read(buffer) = read.as_operation() {
+                    unsafe { self.set_rx_buffer(buffer)? };
+                    rx_len = Some(buffer.len());
+                }
+            }
+        }
 
-        let bad_write = self.0.txd.amount.read().bits() != wr_buffer.len() as u32;
-        let bad_read = self.0.rxd.amount.read().bits() != rd_buffer.len() as u32;
+        // Clear events
+        self.0.events_stopped.reset();
+        self.0.events_suspended.reset();
+        self.0.events_error.reset();
+        self.clear_errorsrc();
+
+        // Chain the writes and reads of the segment with a repeated start, then
+        // either stop or suspend the bus after the last byte.
+        let ends_with_write = tx_len.is_some() && (rx_len.is_none() || starts_with_read);
+        self.0.shorts.write(|w| {
+            if tx_len.is_some() && rx_len.is_some() {
+                if starts_with_read {
+                    w.lastrx_starttx().enabled();
+                } else {
+                    w.lasttx_startrx().enabled();
+                }
+            }
+            match (ends_with_write, segment.suspend) {
+                (true, true) => w.lasttx_suspend().enabled(),
+                (true, false) => w.lasttx_stop().enabled(),
+                // A segment never suspends after reads, see `Segment::next`.
+                (false, _) => w.lastrx_stop().enabled(),
+            }
+        });
 
-        if bad_write {
-            return Err(Error::Transmit);
+        // `1` is a valid value to write to task registers.
+        if starts_with_read {
+            self.0.tasks_startrx.write(|w| unsafe { w.bits(1) });
+        } else {
+            self.0.tasks_starttx.write(|w| unsafe { w.bits(1) });
+        }
+        if resume {
+            self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
         }
 
-        if bad_read {
-            return Err(Error::Receive);
+        Ok((tx_len, rx_len))
+    }
+
+    /// Write to the address register and check that the merged operations of
+    /// `operations` fit in the copy buffer, before anything is put on the bus.
+    fn start_transaction<O: AsOperation>(
+        &mut self,
+        address: u8,
+        operations: &[O],
+    ) -> Result<(), Error> {
+        let mut i = 0;
+        while i < operations.len() {
+            let segment = Segment::next(&operations[i..]);
+            let (tx_copy_len, rx_copy_len) = segment.copy_lens(&operations[i..]);
+            if rx_copy_len > FORCE_COPY_BUFFER_SIZE {
+                return Err(Error::RxBufferTooLong);
+            }
+            if tx_copy_len + rx_copy_len > FORCE_COPY_BUFFER_SIZE {
+                return Err(Error::TxBufferTooLong);
+            }
+            i += segment.len;
         }
 
+        self.0
+            .address
+            .write(|w| unsafe { w.address().bits(address) });
+
         Ok(())
     }
 
+    fn transaction_inner<O: AsOperation>(
+        &mut self,
+        address: u8,
+        mut operations: &mut [O],
+        mut timed_out: impl FnMut() -> bool,
+    ) -> Result<(), Error> {
+        self.start_transaction(address, operations)?;
+
+        let mut copy = [0; FORCE_COPY_BUFFER_SIZE];
+        let mut suspended = false;
+
+        while !operations.is_empty() {
+            let segment = Segment::next(operations);
+            let (current, rest) = core::mem::take(&mut operations).split_at_mut(segment.len);
+            operations = rest;
+
+            let (tx_len, rx_len) = match self.start_segment(current, &segment, suspended, &mut copy)
+            {
+                Ok(lens) => lens,
+                Err(err) => {
+                    if suspended {
+                        self.stop_suspended();
+                    }
+                    return Err(err);
+                }
+            };
+
+            // The transfer is aborted on timeout, leaving nothing to stop.
+            suspended = self.wait_until(&mut timed_out)?;
+
+            if let Err(err) = self.finish(tx_len, rx_len) {
+                if suspended {
+                    self.stop_suspended();
+                }
+                return Err(err);
+            }
+            segment.copy_reads(current, &copy);
+        }
+
+        Ok(())
+    }
+
+    /// Perform a sequence of reads and writes as a single I2C transaction.
+    ///
+    /// Adjacent operations of the same kind are merged, with no repeated start
+    /// or address between them. A read and a write are joined by a repeated
+    /// start condition, and a single stop condition is generated at the end.
+    ///
+    /// The bytes of merged operations, and write buffers that are not located
+    /// in RAM, go through a buffer of `FORCE_COPY_BUFFER_SIZE` bytes on the
+    /// stack, shared by the reads and writes joined by a repeated start.
+    /// Transactions that don't fit fail before any bus activity.
+    ///
+    /// Each buffer must be non-empty and have a length of at most 255 bytes on
+    /// the nRF52832 and at most 65535 bytes on the nRF52840.
+    ///
+    /// Blocks until the transaction has completed; use
+    /// [`Twim::transaction_timeout`] to give up on a slave holding the bus.
+    pub fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        self.transaction_inner(address, operations, || false)
+    }
+
+    /// Perform a transaction like [`Twim::transaction`], giving up once
+    /// `cycles` ticks of `timer` have elapsed.
+    ///
+    /// If the transaction doesn't complete in time, e.g. because a slave holds
+    /// the bus, it is aborted and `Error::Timeout` is returned.
+    /// [`Twim::recover_bus`] can then be used to release the bus.
+    ///
+    /// This method assumes the interrupt for the given timer is NOT enabled,
+    /// and in cases where a timeout does NOT occur, the timer will be left
+    /// running until completion.
+    pub fn transaction_timeout<I>(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+        timer: &mut Timer<I>,
+        cycles: u32,
+    ) -> Result<(), Error>
+    where
+        I: timer::Instance,
+    {
+        timer.start(cycles);
+        self.transaction_inner(address, operations, || timer.wait().is_ok())
+    }
+
+    /// Release a bus that is held by a slave, e.g. one that browned out in the
+    /// middle of a transfer.
+    ///
+    /// This temporarily takes the SCL and SDA pins back from the peripheral and
+    /// drives them as open-drain GPIOs. Up to nine pulses are clocked out on SCL,
+    /// until the slave releases SDA, followed by a STOP condition. The pins are
+    /// handed back to the peripheral afterwards.
+    ///
+    /// `delay` times the clock at roughly 100 kHz. Returns `Error::BusHeld` if
+    /// SDA is still held low after the recovery.
+    pub fn recover_bus<D>(&mut self, delay: &mut D) -> Result<(), Error>
+    where
+        D: DelayUs<u32>,
+    {
+        // The GPIO takes over the pins while the instance is disabled.
+        self.disable();
+
+        let scl_bits = self.0.psel.scl.read().bits();
+        let sda_bits = self.0.psel.sda.read().bits();
+
+        // This is safe, as the pins were handed to the instance in `new`, and the
+        // instance doesn't use them until it is enabled again.
+        let (mut scl, mut sda, sda_in) = unsafe {
+            (
+                Pin::<Output<OpenDrain>>::from_psel_bits(scl_bits),
+                Pin::<Output<OpenDrain>>::from_psel_bits(sda_bits),
+                Pin::<Input<Floating>>::from_psel_bits(sda_bits),
+            )
+        };
+
+        scl.set_high().unwrap();
+        sda.set_high().unwrap();
+        for pin in &[&scl, &sda] {
+            // Keep the input buffer connected, so SDA can be sensed.
+            pin.conf().write(|w| {
+                w.dir()
+                    .output()
+                    .input()
+                    .connect()
+                    .pull()
+                    .pullup()
+                    .drive()
+                    .s0d1()
+                    .sense()
+                    .disabled()
+            });
+        }
+        delay.delay_us(5);
+
+        // Let a slave that holds SDA low finish the byte it is sending.
+        for _ in 0..9 {
+            if sda_in.is_high().unwrap() {
+                break;
+            }
+            scl.set_low().unwrap();
+            delay.delay_us(5);
+            scl.set_high().unwrap();
+            delay.delay_us(5);
+        }
+
+        // Generate a STOP condition: SDA rises while SCL is high.
+        scl.set_low().unwrap();
+        delay.delay_us(5);
+        sda.set_low().unwrap();
+        delay.delay_us(5);
+        scl.set_high().unwrap();
+        delay.delay_us(5);
+        sda.set_high().unwrap();
+        delay.delay_us(5);
+
+        let released = sda_in.is_high().unwrap();
+
+        configure_pin(&scl);
+        configure_pin(&sda);
+        self.enable();
+
+        if released {
+            Ok(())
+        } else {
+            Err(Error::BusHeld)
+        }
+    }
+
+    /// Write to an I2C slave.
+    ///
+    /// The buffer must have a length of at most 255 bytes on the nRF52832
+    /// and at most 65535 bytes on the nRF52840.
+    pub fn write(&mut self, address: u8, buffer: &[u8]) -> Result<(), Error> {
+        self.start_write(address, buffer)?;
+        self.wait();
+        self.finish(Some(buffer.len()), None)
+    }
+
+    /// Read from an I2C slave.
+    ///
+    /// The buffer must have a length of at most 255 bytes on the nRF52832
+    /// and at most 65535 bytes on the nRF52840.
+    pub fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        self.start_read(address, buffer)?;
+        self.wait();
+        self.finish(None, Some(buffer.len()))
+    }
+
+    /// Write data to an I2C slave, then read data from the slave without
+    /// triggering a stop condition between the two.
+    ///
+    /// The buffers must have a length of at most 255 bytes on the nRF52832
+    /// and at most 65535 bytes on the nRF52840.
+    pub fn write_then_read(
+        &mut self,
+        address: u8,
+        wr_buffer: &[u8],
+        rd_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.start_write_then_read(address, wr_buffer, rd_buffer)?;
+        self.wait();
+        self.finish(Some(wr_buffer.len()), Some(rd_buffer.len()))
+    }
+
     /// Copy data into RAM and write to an I2C slave, then read data from the slave without
     /// triggering a stop condition between the two.
     ///
@@ -383,6 +731,169 @@ where
     }
 }
 
+impl<T> Twim<T>
+where
+    T: Instance,
+{
+    /// Prepare an EasyDMA array-list reception of fixed-size records, without
+    /// starting it.
+    ///
+    /// Each time the STARTTX task is triggered, e.g. through PPI from a TIMER
+    /// event, `command` is written to the slave at `address`, followed by a
+    /// repeated start and a read of one record of `record_len` bytes into the
+    /// next slot of `records`, and a stop condition. The CPU isn't involved
+    /// between records.
+    ///
+    /// Errors, like a NACK from the slave, are not handled while the reception
+    /// is running.
+    ///
+    /// # Safety
+    ///
+    /// EasyDMA advances through RAM with each record and doesn't stop at the end
+    /// of `records`. The STARTTX task must not be triggered more than
+    /// `records.len() / record_len` times before the transfer is stopped.
+    pub unsafe fn read_list<TX, RX>(
+        mut self,
+        address: u8,
+        command: TX,
+        mut records: RX,
+        record_len: usize,
+    ) -> Result<ListTransfer<T, TX, RX>, (Error, Self, TX, RX)>
+    where
+        TX: ReadBuffer<Word = u8> + 'static,
+        RX: WriteBuffer<Word = u8> + 'static,
+    {
+        let (tx_ptr, tx_len) = command.read_buffer();
+        let (rx_ptr, rx_len) = records.write_buffer();
+
+        if tx_len == 0 {
+            return Err((Error::TxBufferZeroLength, self, command, records));
+        }
+        if tx_len > EASY_DMA_SIZE {
+            return Err((Error::TxBufferTooLong, self, command, records));
+        }
+        if record_len == 0 {
+            return Err((Error::RxBufferZeroLength, self, command, records));
+        }
+        if record_len > EASY_DMA_SIZE || record_len > rx_len {
+            return Err((Error::RxBufferTooLong, self, command, records));
+        }
+        if (tx_ptr as usize) < SRAM_LOWER || (tx_ptr as usize) > SRAM_UPPER {
+            return Err((Error::DMABufferNotInDataMemory, self, command, records));
+        }
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // before any DMA action has started.
+        compiler_fence(SeqCst);
+
+        self.0
+            .address
+            .write(|w| w.address().bits(address));
+
+        // The command is sent from the same location for every record.
+        self.0.txd.list.write(|w| w.list().disabled());
+        self.0.txd.ptr.write(|w| w.ptr().bits(tx_ptr as u32));
+        self.0.txd.maxcnt.write(|w| w.maxcnt().bits(tx_len as _));
+
+        // Each record moves the receive pointer on by MAXCNT bytes.
+        self.0.rxd.list.write(|w| w.list().array_list());
+        self.0.rxd.ptr.write(|w| w.ptr().bits(rx_ptr as u32));
+        self.0.rxd.maxcnt.write(|w| w.maxcnt().bits(record_len as _));
+
+        // Clear events
+        self.0.events_stopped.reset();
+        self.0.events_error.reset();
+        self.clear_errorsrc();
+
+        self.0.shorts.write(|w| {
+            w.lasttx_startrx().enabled();
+            w.lastrx_stop().enabled();
+            w
+        });
+
+        Ok(ListTransfer {
+            inner: Some(ListInner {
+                twim: self,
+                command,
+                records,
+                rx_ptr: rx_ptr as u32,
+                record_len,
+            }),
+        })
+    }
+}
+
+/// An EasyDMA array-list reception, see [`Twim::read_list`].
+pub struct ListTransfer<T: Instance, TX, RX> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<ListInner<T, TX, RX>>,
+}
+
+struct ListInner<T, TX, RX> {
+    twim: Twim<T>,
+    command: TX,
+    records: RX,
+    rx_ptr: u32,
+    record_len: usize,
+}
+
+impl<T: Instance, TX, RX> ListTransfer<T, TX, RX> {
+    fn twim(&self) -> &T {
+        &self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+            .twim
+            .0
+    }
+
+    /// Returns reference to the STARTTX task endpoint for PPI, starting the next record.
+    #[inline(always)]
+    pub fn task_starttx(&self) -> &twim0::TASKS_STARTTX {
+        &self.twim().tasks_starttx
+    }
+
+    /// Returns reference to the STOPPED event endpoint for PPI, signalling a received record.
+    #[inline(always)]
+    pub fn event_stopped(&self) -> &twim0::EVENTS_STOPPED {
+        &self.twim().events_stopped
+    }
+
+    /// Returns the number of records received so far.
+    pub fn received(&self) -> usize {
+        let inner = self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        (self.twim().rxd.ptr.read().bits() - inner.rx_ptr) as usize / inner.record_len
+    }
+
+    /// Stops the reception and returns the TWIM and the buffers, along with
+    /// the first error that occurred, if any.
+    ///
+    /// Make sure the STARTTX task is no longer triggered before calling this.
+    pub fn stop(mut self) -> (Twim<T>, TX, RX, Result<(), Error>) {
+        let mut inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        let res = inner.twim.read_errorsrc();
+        inner.twim.abort();
+        inner.twim.0.rxd.list.write(|w| w.list().disabled());
+        (inner.twim, inner.command, inner.records, res)
+    }
+}
+
+impl<T: Instance, TX, RX> Drop for ListTransfer<T, TX, RX> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.twim.abort();
+            inner.twim.0.rxd.list.write(|w| w.list().disabled());
+        }
+    }
+}
+
 impl<T> embedded_hal::blocking::i2c::Write for Twim<T>
 where
     T: Instance,
@@ -434,6 +945,385 @@ where
     }
 }
 
+#[cfg(feature = "embedded-hal-1")]
+impl<T> eh1::i2c::ErrorType for Twim<T>
+where
+    T: Instance,
+{
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T> eh1::i2c::I2c for Twim<T>
+where
+    T: Instance,
+{
+    /// Performs a sequence of operations on the bus.
+    ///
+    /// See [`Twim::transaction`] for how the operations are performed.
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [eh1::i2c::Operation<'_>],
+    ) -> Result<(), Error> {
+        self.transaction_inner(address, operations, || false)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Twim<T>
+where
+    T: Instance,
+{
+    /// Handles the TWIM interrupt for the async API.
+    ///
+    /// This must be called from the interrupt handler of instance `T` when using any of the
+    /// `*_async` methods, and the interrupt must be unmasked in the NVIC.
+    pub fn on_interrupt() {
+        let twim = unsafe { &*T::ptr() };
+
+        // The awaiting future checks the events itself and re-enables the interrupts as needed.
+        twim.intenclr
+            .write(|w| w.stopped().clear().suspended().clear().error().clear());
+        T::waker().wake();
+    }
+
+    /// Wait for stop, suspend or error without blocking the executor.
+    ///
+    /// Returns `true` if the bus has been suspended rather than stopped.
+    async fn wait_async(&mut self) -> bool {
+        // Stop the transfer if the future is dropped before it completes, so the
+        // DMA doesn't keep accessing buffers that are about to go out of scope.
+        let on_drop = crate::asynch::OnDrop::new(|| {
+            let twim = unsafe { &*T::ptr() };
+            twim.intenclr
+                .write(|w| w.stopped().clear().suspended().clear().error().clear());
+            stop_bounded(twim);
+        });
+
+        let suspended = core::future::poll_fn(|cx| {
+            T::waker().register(cx.waker());
+
+            if self.0.events_stopped.read().bits() != 0 {
+                self.0.events_stopped.reset();
+                return core::task::Poll::Ready(false);
+            }
+            if self.0.events_suspended.read().bits() != 0 {
+                self.0.events_suspended.reset();
+                return core::task::Poll::Ready(true);
+            }
+            if self.0.events_error.read().bits() != 0 {
+                self.0.events_error.reset();
+                self.0.tasks_resume.write(|w| unsafe { w.bits(1) });
+                self.0.tasks_stop.write(|w| unsafe { w.bits(1) });
+            }
+
+            self.0
+                .intenset
+                .write(|w| w.stopped().set().suspended().set().error().set());
+            core::task::Poll::Pending
+        })
+        .await;
+
+        on_drop.defuse();
+        suspended
+    }
+
+    /// Write to an I2C slave without blocking.
+    ///
+    /// See [`Twim::write`] for the buffer requirements.
+    pub async fn write_async(&mut self, address: u8, buffer: &[u8]) -> Result<(), Error> {
+        self.start_write(address, buffer)?;
+        self.wait_async().await;
+        self.finish(Some(buffer.len()), None)
+    }
+
+    /// Read from an I2C slave without blocking.
+    ///
+    /// See [`Twim::read`] for the buffer requirements.
+    pub async fn read_async(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Error> {
+        self.start_read(address, buffer)?;
+        self.wait_async().await;
+        self.finish(None, Some(buffer.len()))
+    }
+
+    /// Write data to an I2C slave, then read data from the slave without triggering a stop
+    /// condition between the two, without blocking.
+    ///
+    /// See [`Twim::write_then_read`] for the buffer requirements.
+    pub async fn write_then_read_async(
+        &mut self,
+        address: u8,
+        wr_buffer: &[u8],
+        rd_buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.start_write_then_read(address, wr_buffer, rd_buffer)?;
+        self.wait_async().await;
+        self.finish(Some(wr_buffer.len()), Some(rd_buffer.len()))
+    }
+
+    async fn transaction_inner_async<O: AsOperation>(
+        &mut self,
+        address: u8,
+        mut operations: &mut [O],
+    ) -> Result<(), Error> {
+        self.start_transaction(address, operations)?;
+
+        let mut copy = [0; FORCE_COPY_BUFFER_SIZE];
+        let mut suspended = false;
+
+        while !operations.is_empty() {
+            let segment = Segment::next(operations);
+            let (current, rest) = core::mem::take(&mut operations).split_at_mut(segment.len);
+            operations = rest;
+
+            let res = match self.start_segment(current, &segment, suspended, &mut copy) {
+                Ok((tx_len, rx_len)) => {
+                    suspended = self.wait_async().await;
+                    self.finish(tx_len, rx_len)
+                        .map(|()| segment.copy_reads(current, &copy))
+                }
+                Err(err) => Err(err),
+            };
+
+            if let Err(err) = res {
+                if suspended {
+                    self.stop_suspended();
+                }
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Perform a sequence of reads and writes as a single I2C transaction,
+    /// without blocking.
+    ///
+    /// See [`Twim::transaction`] for how the operations are performed.
+    pub async fn transaction_async(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Error> {
+        self.transaction_inner_async(address, operations).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> embedded_hal_async::i2c::I2c for Twim<T>
+where
+    T: Instance,
+{
+    /// Performs a sequence of operations on the bus.
+    ///
+    /// See [`Twim::transaction`] for how the operations are performed.
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [eh1::i2c::Operation<'_>],
+    ) -> Result<(), Error> {
+        self.transaction_inner_async(address, operations).await
+    }
+}
+
+/// Configure a pin the way the TWIM peripheral requires it, see `Twim::new`.
+fn configure_pin<MODE>(pin: &Pin<MODE>) {
+    pin.conf().write(|w| {
+        w.dir()
+            .input()
+            .input()
+            .connect()
+            .pull()
+            .pullup()
+            .drive()
+            .s0d1()
+            .sense()
+            .disabled()
+    });
+}
+
+/// Number of times the STOPPED event is polled before giving up on a STOP.
+///
+/// A STOP normally completes within a byte time, well under a millisecond at
+/// 100 kHz; this bound is several milliseconds at 64 MHz.
+const STOP_POLLS: u32 = 100_000;
+
+/// Stop the transfer of `twim` and wait for the STOPPED event.
+///
+/// A slave holding the bus can keep the STOP from ever completing, so the
+/// instance is disabled to end the transfer if STOPPED doesn't come in time.
+fn stop_bounded(twim: &twim0::RegisterBlock) {
+    twim.events_stopped.reset();
+    twim.tasks_resume.write(|w| unsafe { w.bits(1) });
+    twim.tasks_stop.write(|w| unsafe { w.bits(1) });
+
+    let stopped = (0..STOP_POLLS).any(|_| twim.events_stopped.read().bits() != 0);
+    if !stopped {
+        twim.enable.write(|w| w.enable().disabled());
+        twim.enable.write(|w| w.enable().enabled());
+    }
+
+    twim.events_stopped.reset();
+    twim.events_suspended.reset();
+    twim.events_error.reset();
+
+    // Conservative compiler fence to prevent optimizations that do not
+    // take in to account actions by DMA. The fence has been placed here,
+    // after all possible DMA actions have completed.
+    compiler_fence(SeqCst);
+}
+
+/// A group of operations that is performed by a single start of the peripheral.
+struct Segment {
+    /// Number of operations in the segment.
+    len: usize,
+    /// Number of operations before the repeated start, all of the same kind.
+    first: usize,
+    /// Whether to suspend the bus after the segment, because more operations follow.
+    suspend: bool,
+}
+
+impl Segment {
+    /// Returns the segment at the start of `operations`, which must not be
+    /// empty.
+    ///
+    /// Adjacent operations of the same kind are merged into a single transfer.
+    /// A segment holds at most one such group of writes and one of reads,
+    /// chained by a repeated start. The bus can only be suspended after a
+    /// write, so a segment that ends with reads must end the transaction.
+    fn next<O: AsOperation>(operations: &[O]) -> Self {
+        let first = same_kind_len(operations);
+        let second = same_kind_len(&operations[first..]);
+        let more = operations.len() > first + second;
+
+        let (len, suspend) = match (operations[0].is_read(), second) {
+            // Writes on their own, as following reads may need to be chained
+            // to writes of their own.
+            (false, n) if n == 0 || more => (first, n != 0),
+            // Writes followed by reads that end the transaction, or reads
+            // followed by writes.
+            _ => (first + second, more),
+        };
+
+        Segment {
+            len,
+            first,
+            suspend,
+        }
+    }
+
+    /// Returns the number of bytes of the copy buffer needed for the writes and
+    /// the reads of the segment at the start of `operations`.
+    ///
+    /// Merged reads are received into the copy buffer, while a single read is
+    /// received in place. Merged writes are copied into it, as is a single
+    /// write buffer not located in RAM.
+    fn copy_lens<O: AsOperation>(&self, operations: &[O]) -> (usize, usize) {
+        let (first, second) = operations[..self.len].split_at(self.first);
+        let (writes, reads) = if first[0].is_read() {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        let total = |group: &[O]| group.iter().map(|o| o.bytes().len()).sum::<usize>();
+
+        let tx_len = match writes {
+            [write] if slice_in_ram(write.bytes()) => 0,
+            _ => total(writes),
+        };
+        let rx_len = if reads.len() > 1 { total(reads) } else { 0 };
+        (tx_len, rx_len)
+    }
+
+    /// Copy merged reads of the segment at the start of `operations` from the
+    /// start of `copy` into their buffers.
+    fn copy_reads<O: AsOperation>(&self, operations: &mut [O], copy: &[u8]) {
+        let (_, rx_copy_len) = self.copy_lens(operations);
+        if rx_copy_len == 0 {
+            return;
+        }
+
+        let mut received = &copy[..rx_copy_len];
+        for operation in operations[..self.len].iter_mut() {
+            if let Operation::Read(buffer) = operation.as_operation() {
+                let (bytes, rest) = received.split_at(buffer.len());
+                buffer.copy_from_slice(bytes);
+                received = rest;
+            }
+        }
+    }
+}
+
+/// Returns the number of operations at the start of `operations` of the same
+/// kind as the first one.
+fn same_kind_len<O: AsOperation>(operations: &[O]) -> usize {
+    match operations.first() {
+        Some(first) => operations
+            .iter()
+            .take_while(|o| o.is_read() == first.is_read())
+            .count(),
+        None => 0,
+    }
+}
+
+/// Common interface to this module's and embedded-hal's transaction operations.
+trait AsOperation {
+    fn is_read(&self) -> bool;
+    fn bytes(&self) -> &[u8];
+    fn as_operation(&mut self) -> Operation<'_>;
+}
+
+impl AsOperation for Operation<'_> {
+    fn is_read(&self) -> bool {
+        matches!(self, Operation::Read(_))
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Operation::Read(buffer) => buffer,
+            Operation::Write(buffer) => buffer,
+        }
+    }
+
+    fn as_operation(&mut self) -> Operation<'_> {
+        match self {
+            Operation::Read(buffer) => Operation::Read(buffer),
+            Operation::Write(buffer) => Operation::Write(buffer),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl AsOperation for eh1::i2c::Operation<'_> {
+    fn is_read(&self) -> bool {
+        matches!(self, eh1::i2c::Operation::Read(_))
+    }
+
+    fn bytes(&self) -> &[u8] {
+        match self {
+            eh1::i2c::Operation::Read(buffer) => buffer,
+            eh1::i2c::Operation::Write(buffer) => buffer,
+        }
+    }
+
+    fn as_operation(&mut self) -> Operation<'_> {
+        match self {
+            eh1::i2c::Operation::Read(buffer) => Operation::Read(buffer),
+            eh1::i2c::Operation::Write(buffer) => Operation::Write(buffer),
+        }
+    }
+}
+
+/// An operation of an I2C transaction, see [`Twim::transaction`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum Operation<'a> {
+    /// Read from the slave into the buffer.
+    Read(&'a mut [u8]),
+    /// Write the buffer to the slave.
+    Write(&'a [u8]),
+}
+
 /// The pins used by the TWIM peripheral.
 ///
 /// Currently, only P0 pins are supported.
@@ -446,6 +1336,7 @@ pub struct Pins {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[non_exhaustive]
 pub enum Error {
     TxBufferTooLong,
     RxBufferTooLong,
@@ -457,17 +1348,58 @@ pub enum Error {
     AddressNack,
     DataNack,
     Overrun,
+    /// The transaction didn't complete in time and has been aborted.
+    Timeout,
+    /// SDA is still held low after [`Twim::recover_bus`].
+    BusHeld,
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl eh1::i2c::Error for Error {
+    fn kind(&self) -> eh1::i2c::ErrorKind {
+        use eh1::i2c::{ErrorKind, NoAcknowledgeSource};
+
+        match *self {
+            Error::AddressNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address),
+            Error::DataNack => ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data),
+            Error::Overrun => ErrorKind::Overrun,
+            _ => ErrorKind::Other,
+        }
+    }
 }
 
 /// Implemented by all TWIM instances
-pub trait Instance: Deref<Target = twim0::RegisterBlock> + sealed::Sealed {}
+pub trait Instance: Deref<Target = twim0::RegisterBlock> + sealed::Sealed {
+    /// Returns a pointer to the instance's register block.
+    fn ptr() -> *const twim0::RegisterBlock;
+}
 
 mod sealed {
-    pub trait Sealed {}
+    pub trait Sealed {
+        #[cfg(feature = "async")]
+        fn waker() -> &'static crate::asynch::AtomicWaker;
+    }
+}
+
+macro_rules! impl_instance {
+    ($TWIMx:ident) => {
+        impl sealed::Sealed for $TWIMx {
+            #[cfg(feature = "async")]
+            fn waker() -> &'static crate::asynch::AtomicWaker {
+                static WAKER: crate::asynch::AtomicWaker = crate::asynch::AtomicWaker::new();
+                &WAKER
+            }
+        }
+
+        impl Instance for $TWIMx {
+            fn ptr() -> *const twim0::RegisterBlock {
+                $TWIMx::ptr()
+            }
+        }
+    };
 }
 
-impl sealed::Sealed for TWIM0 {}
-impl Instance for TWIM0 {}
+impl_instance!(TWIM0);
 
 #[cfg(any(
     feature = "52832",
@@ -477,20 +1409,17 @@ impl Instance for TWIM0 {}
 ))]
 mod _twim1 {
     use super::*;
-    impl sealed::Sealed for TWIM1 {}
-    impl Instance for TWIM1 {}
+    impl_instance!(TWIM1);
 }
 
 #[cfg(feature = "9160")]
 mod _twim2 {
     use super::*;
-    impl sealed::Sealed for TWIM2 {}
-    impl Instance for TWIM2 {}
+    impl_instance!(TWIM2);
 }
 
 #[cfg(feature = "9160")]
 mod _twim3 {
     use super::*;
-    impl sealed::Sealed for TWIM3 {}
-    impl Instance for TWIM3 {}
+    impl_instance!(TWIM3);
 }
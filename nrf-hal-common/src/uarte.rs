@@ -5,9 +5,11 @@
 //! - nrf52832: Section 35
 //! - nrf52840: Section 6.34
 use core::fmt;
+use core::marker::PhantomData;
 use core::ops::Deref;
 use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
 
+use embedded_dma::{ReadBuffer, WriteBuffer};
 use embedded_hal::digital::v2::OutputPin;
 
 #[cfg(any(feature = "52833", feature = "52840"))]
@@ -20,6 +22,7 @@ use crate::pac::{uarte0_ns as uarte0, UARTE0_NS as UARTE0, UARTE1_NS as UARTE1};
 use crate::pac::{uarte0, UARTE0};
 
 use crate::gpio::{Floating, Input, Output, Pin, PushPull};
+use crate::pac::Interrupt;
 use crate::prelude::*;
 use crate::slice_in_ram_or;
 use crate::target_constants::EASY_DMA_SIZE;
@@ -167,6 +170,21 @@ where
     /// The buffer must have a length of at most 255 bytes on the nRF52832
     /// and at most 65535 bytes on the nRF52840.
     pub fn write(&mut self, tx_buffer: &[u8]) -> Result<(), Error> {
+        self.start_write(tx_buffer)?;
+
+        // Wait for transmission to end.
+        while self.0.events_endtx.read().bits() == 0 {
+            // TODO: Do something here which uses less power. Like `wfi`.
+        }
+
+        self.stop_write();
+
+        Ok(())
+    }
+
+    /// Start a UARTE write transaction by setting the control
+    /// values and triggering a write task.
+    fn start_write(&mut self, tx_buffer: &[u8]) -> Result<(), Error> {
         if tx_buffer.len() > EASY_DMA_SIZE {
             return Err(Error::TxBufferTooLong);
         }
@@ -206,12 +224,11 @@ where
             // `1` is a valid value to write to task registers.
             unsafe { w.bits(1) });
 
-                   
-        // Wait for transmission to end.
-        while self.0.events_endtx.read().bits() == 0 {
-            // TODO: Do something here which uses less power. Like `wfi`.
-        }
+        Ok(())
+    }
 
+    /// Finish a UARTE write transaction by stopping the transmitter.
+    fn stop_write(&mut self) {
         // Conservative compiler fence to prevent optimizations that do not
         // take in to account actions by DMA. The fence has been placed here,
         // after all possible DMA actions have completed.
@@ -230,8 +247,6 @@ where
         while self.0.events_txstopped.read().bits() == 0 {
             // Spin
         }
-
-        Ok(())
     }
 
     /// Read via UARTE.
@@ -389,6 +404,24 @@ where
         // The event flag itself is later reset by `finalize_read`.
     }
 
+    /// Splits the UARTE into an independent transmitter and receiver.
+    ///
+    /// Each half starts its own EasyDMA transfers and can be moved into a different context, like
+    /// separate RTIC tasks. Both halves share the instance's interrupt (`T::INTERRUPT`).
+    pub fn split(self) -> (UarteTx<T>, UarteRx<T>) {
+        (
+            UarteTx { uarte: self.0 },
+            UarteRx {
+                _uarte: PhantomData,
+            },
+        )
+    }
+
+    /// Reassembles a `Uarte` from the halves returned by `split`.
+    pub fn join(tx: UarteTx<T>, _rx: UarteRx<T>) -> Self {
+        Uarte(tx.uarte)
+    }
+
     /// Return the raw interface to the underlying UARTE peripheral.
     pub fn free(self) -> (T, Pins) {
         let rxd = self.0.psel.rxd.read();
@@ -432,6 +465,779 @@ where
     }
 }
 
+#[cfg(feature = "embedded-hal-1")]
+impl<T> embedded_io::ErrorType for Uarte<T>
+where
+    T: Instance,
+{
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T> embedded_io::Read for Uarte<T>
+where
+    T: Instance,
+{
+    /// Blocks until a single byte has been received.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        Uarte::read(self, &mut buf[..1])?;
+        Ok(1)
+    }
+}
+
+#[cfg(feature = "embedded-hal-1")]
+impl<T> embedded_io::Write for Uarte<T>
+where
+    T: Instance,
+{
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        use crate::target_constants::FORCE_COPY_BUFFER_SIZE;
+
+        if crate::slice_in_ram(buf) {
+            let len = buf.len().min(EASY_DMA_SIZE);
+            Uarte::write(self, &buf[..len])?;
+            Ok(len)
+        } else {
+            // Copy the data into an on-stack buffer so we never try to
+            // EasyDMA from flash.
+            let len = buf.len().min(FORCE_COPY_BUFFER_SIZE);
+            let mut ram_buf = [0; FORCE_COPY_BUFFER_SIZE];
+            ram_buf[..len].copy_from_slice(&buf[..len]);
+            Uarte::write(self, &ram_buf[..len])?;
+            Ok(len)
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        // `write` blocks until the transmitter has stopped.
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Uarte<T>
+where
+    T: Instance,
+{
+    /// Handles the UARTE interrupt for the async API.
+    ///
+    /// This must be called from the interrupt handler of instance `T` (`T::INTERRUPT`) when
+    /// using any of the `*_async` methods, and the interrupt must be unmasked in the NVIC.
+    pub fn on_interrupt() {
+        let uarte = unsafe { &*T::ptr() };
+
+        // The awaiting future checks the events itself and re-enables the interrupts as needed.
+        uarte.intenclr.write(|w| w.endtx().clear().endrx().clear());
+        T::waker().wake();
+    }
+
+    /// Write via UARTE without blocking.
+    ///
+    /// See [`Uarte::write`] for the buffer requirements.
+    pub async fn write_async(&mut self, tx_buffer: &[u8]) -> Result<(), Error> {
+        self.start_write(tx_buffer)?;
+
+        // Stop the transmitter if the future is dropped before it completes, so
+        // the DMA doesn't keep reading from a buffer that is about to go out of scope.
+        let on_drop = crate::asynch::OnDrop::new(|| {
+            let uarte = unsafe { &*T::ptr() };
+            uarte.intenclr.write(|w| w.endtx().clear());
+            uarte.events_txstopped.reset();
+            uarte.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+            while uarte.events_txstopped.read().bits() == 0 {}
+        });
+
+        core::future::poll_fn(|cx| {
+            T::waker().register(cx.waker());
+
+            if self.0.events_endtx.read().bits() != 0 {
+                return core::task::Poll::Ready(());
+            }
+
+            self.0.intenset.write(|w| w.endtx().set());
+            core::task::Poll::Pending
+        })
+        .await;
+
+        on_drop.defuse();
+        self.stop_write();
+
+        Ok(())
+    }
+
+    /// Read via UARTE without blocking, until `rx_buffer` is full.
+    ///
+    /// See [`Uarte::read`] for the buffer requirements.
+    pub async fn read_async(&mut self, rx_buffer: &mut [u8]) -> Result<(), Error> {
+        self.start_read(rx_buffer)?;
+
+        // Stop the receiver if the future is dropped before it completes, so
+        // the DMA doesn't keep writing to a buffer that is about to go out of scope.
+        let (ptr, len) = (rx_buffer.as_ptr() as u32, rx_buffer.len());
+        let on_drop = crate::asynch::OnDrop::new(|| {
+            let uarte = unsafe { &*T::ptr() };
+            uarte.intenclr.write(|w| w.endrx().clear());
+            stop_read(uarte, ptr, len);
+        });
+
+        core::future::poll_fn(|cx| {
+            T::waker().register(cx.waker());
+
+            if self.0.events_endrx.read().bits() != 0 {
+                return core::task::Poll::Ready(());
+            }
+
+            self.0.intenset.write(|w| w.endrx().set());
+            core::task::Poll::Pending
+        })
+        .await;
+
+        on_drop.defuse();
+        self.finalize_read();
+
+        if self.0.rxd.amount.read().bits() != rx_buffer.len() as u32 {
+            return Err(Error::Receive);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> embedded_io_async::Read for Uarte<T>
+where
+    T: Instance,
+{
+    /// Waits until a single byte has been received.
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.read_async(&mut buf[..1]).await?;
+        Ok(1)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> embedded_io_async::Write for Uarte<T>
+where
+    T: Instance,
+{
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        use crate::target_constants::FORCE_COPY_BUFFER_SIZE;
+
+        if crate::slice_in_ram(buf) {
+            let len = buf.len().min(EASY_DMA_SIZE);
+            self.write_async(&buf[..len]).await?;
+            Ok(len)
+        } else {
+            // Copy the data into an on-stack buffer so we never try to
+            // EasyDMA from flash.
+            let len = buf.len().min(FORCE_COPY_BUFFER_SIZE);
+            let mut ram_buf = [0; FORCE_COPY_BUFFER_SIZE];
+            ram_buf[..len].copy_from_slice(&buf[..len]);
+            self.write_async(&ram_buf[..len]).await?;
+            Ok(len)
+        }
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        // `write` waits until the transmitter has stopped.
+        Ok(())
+    }
+}
+
+/// Transmitting half of a split `Uarte`.
+pub struct UarteTx<T> {
+    uarte: T,
+}
+
+impl<T> UarteTx<T>
+where
+    T: Instance,
+{
+    /// Starts transmitting `buffer` via EasyDMA and returns immediately.
+    ///
+    /// The returned `TxTransfer` owns the buffer until the transmission has ended. Enable the
+    /// `ENDTX` interrupt with `enable_interrupt` to be notified of completion.
+    ///
+    /// The buffer must reside in RAM and have a length of at most 255 bytes on the nRF52832
+    /// and at most 65535 bytes on the nRF52840.
+    pub fn write_dma<B>(self, buffer: B) -> Result<TxTransfer<T, B>, (Error, Self, B)>
+    where
+        B: ReadBuffer<Word = u8> + 'static,
+    {
+        let (ptr, len) = unsafe { buffer.read_buffer() };
+        if len > EASY_DMA_SIZE {
+            return Err((Error::TxBufferTooLong, self, buffer));
+        }
+        // NOTE(unsafe) only used to check the location of the buffer
+        let slice = unsafe { core::slice::from_raw_parts(ptr, len) };
+        if let Err(err) = slice_in_ram_or(slice, Error::BufferNotInRAM) {
+            return Err((err, self, buffer));
+        }
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // before any DMA action has started.
+        compiler_fence(SeqCst);
+
+        // The buffer is owned by the returned transfer, so it stays valid for
+        // as long as the DMA may access it.
+        self.uarte.txd.ptr.write(|w| unsafe { w.ptr().bits(ptr as u32) });
+        self.uarte
+            .txd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(len as _) });
+
+        self.uarte.events_endtx.reset();
+        self.uarte.events_txstopped.reset();
+        self.uarte.tasks_starttx.write(|w| unsafe { w.bits(1) });
+
+        Ok(TxTransfer {
+            inner: Some(TxInner { buffer, tx: self }),
+        })
+    }
+
+    /// Enables the `ENDTX` interrupt, fired when a transmission has ended.
+    ///
+    /// Note that the interrupt also has to be unmasked in the NVIC, or the
+    /// handler won't get called.
+    pub fn enable_interrupt(&mut self) {
+        self.uarte.intenset.write(|w| w.endtx().set());
+    }
+
+    /// Disables the `ENDTX` interrupt.
+    pub fn disable_interrupt(&mut self) {
+        self.uarte.intenclr.write(|w| w.endtx().clear());
+    }
+}
+
+/// Receiving half of a split `Uarte`.
+pub struct UarteRx<T> {
+    _uarte: PhantomData<T>,
+}
+
+impl<T> UarteRx<T>
+where
+    T: Instance,
+{
+    fn uarte(&self) -> &uarte0::RegisterBlock {
+        // NOTE(unsafe) `UarteRx` only accesses the receive-related registers,
+        // which are never touched by `UarteTx`.
+        unsafe { &*T::ptr() }
+    }
+
+    /// Starts receiving into `buffer` via EasyDMA and returns immediately.
+    ///
+    /// The returned `RxTransfer` owns the buffer until it has been filled or the reception has
+    /// been cancelled. Enable the `ENDRX` interrupt with `enable_interrupt` to be notified of
+    /// completion.
+    ///
+    /// The buffer must have a length of at most 255 bytes on the nRF52832
+    /// and at most 65535 bytes on the nRF52840.
+    #[allow(unused_mut)]
+    pub fn read_dma<B>(self, mut buffer: B) -> Result<RxTransfer<T, B>, (Error, Self, B)>
+    where
+        B: WriteBuffer<Word = u8> + 'static,
+    {
+        let (ptr, len) = unsafe { buffer.write_buffer() };
+        if len > EASY_DMA_SIZE {
+            return Err((Error::RxBufferTooLong, self, buffer));
+        }
+        // NOTE(unsafe) only used to check the location of the buffer
+        let slice = unsafe { core::slice::from_raw_parts(ptr as *const u8, len) };
+        if let Err(err) = slice_in_ram_or(slice, Error::BufferNotInRAM) {
+            return Err((err, self, buffer));
+        }
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // before any DMA action has started.
+        compiler_fence(SeqCst);
+
+        let uarte = self.uarte();
+        uarte.rxd.ptr.write(|w| unsafe { w.ptr().bits(ptr as u32) });
+        uarte
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(len as _) });
+
+        uarte.events_endrx.reset();
+        uarte.events_rxto.reset();
+        uarte.tasks_startrx.write(|w| unsafe { w.bits(1) });
+
+        Ok(RxTransfer {
+            inner: Some(RxInner {
+                buffer,
+                ptr: ptr as u32,
+                len,
+                rx: self,
+            }),
+        })
+    }
+
+    /// Enables the `ENDRX` interrupt, fired when the receive buffer is full or the reception
+    /// has been stopped.
+    ///
+    /// Note that the interrupt also has to be unmasked in the NVIC, or the
+    /// handler won't get called.
+    pub fn enable_interrupt(&mut self) {
+        self.uarte().intenset.write(|w| w.endrx().set());
+    }
+
+    /// Disables the `ENDRX` interrupt.
+    pub fn disable_interrupt(&mut self) {
+        self.uarte().intenclr.write(|w| w.endrx().clear());
+    }
+
+    /// Turns the receiver into a continuous, double-buffered stream.
+    ///
+    /// Reception alternates between the two `buffers` using the `ENDRX` -> `STARTRX` shortcut, so
+    /// no bytes are lost while a filled buffer is being processed. The `timer` is used to detect
+    /// an idle line: `ppi_byte` restarts it on every received byte and `ppi_idle` stops the
+    /// current reception once no byte has been received for `idle_us` microseconds, handing the
+    /// partially filled buffer to the application.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if a buffer is empty or longer than `EASY_DMA_SIZE`.
+    #[cfg(not(feature = "9160"))]
+    pub fn into_stream<I, U, P1, P2>(
+        self,
+        timer: Timer<I, U>,
+        idle_us: u32,
+        mut ppi_byte: P1,
+        mut ppi_idle: P2,
+        buffers: [&'static mut [u8]; 2],
+    ) -> UarteRxStream<T, I, P1, P2>
+    where
+        I: timer::Instance,
+        P1: ConfigurablePpi,
+        P2: ConfigurablePpi,
+    {
+        for buffer in buffers.iter() {
+            assert!(!buffer.is_empty() && buffer.len() <= EASY_DMA_SIZE);
+        }
+
+        let timer = timer.free();
+        timer.set_oneshot();
+        timer.as_timer0().cc[0].write(|w| unsafe { w.cc().bits(idle_us) });
+        timer.as_timer0().tasks_stop.write(|w| unsafe { w.bits(1) });
+        timer.as_timer0().tasks_clear.write(|w| unsafe { w.bits(1) });
+        timer.timer_reset_event();
+
+        let uarte = self.uarte();
+
+        // Every received byte restarts the idle timer...
+        ppi_byte.set_event_endpoint(&uarte.events_rxdrdy);
+        ppi_byte.set_task_endpoint(&timer.as_timer0().tasks_clear);
+        ppi_byte.set_fork_task_endpoint(&timer.as_timer0().tasks_start);
+
+        // ...and an expired idle timer ends the current buffer early.
+        ppi_idle.set_event_endpoint(&timer.as_timer0().events_compare[0]);
+        ppi_idle.set_task_endpoint(&uarte.tasks_stoprx);
+
+        ppi_byte.enable();
+        ppi_idle.enable();
+
+        // Restart reception automatically once a buffer has been filled.
+        uarte.shorts.write(|w| w.endrx_startrx().enabled());
+
+        compiler_fence(SeqCst);
+
+        uarte
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(buffers[0].as_ptr() as u32) });
+        uarte
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits(buffers[0].len() as _) });
+
+        uarte.events_endrx.reset();
+        uarte.events_rxstarted.reset();
+        uarte.events_rxto.reset();
+        uarte.tasks_startrx.write(|w| unsafe { w.bits(1) });
+
+        UarteRxStream {
+            inner: Some(RxStreamInner {
+                rx: self,
+                timer,
+                ppi_byte,
+                ppi_idle,
+                buffers,
+                active: 0,
+                next_pending: false,
+            }),
+        }
+    }
+}
+
+/// A continuous, double-buffered reception created by `UarteRx::into_stream`.
+///
+/// Dropping the stream stops the reception.
+#[cfg(not(feature = "9160"))]
+pub struct UarteRxStream<T, I, P1, P2>
+where
+    T: Instance,
+    I: timer::Instance,
+    P1: ConfigurablePpi,
+    P2: ConfigurablePpi,
+{
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<RxStreamInner<T, I, P1, P2>>,
+}
+
+#[cfg(not(feature = "9160"))]
+struct RxStreamInner<T: Instance, I, P1, P2> {
+    rx: UarteRx<T>,
+    timer: I,
+    ppi_byte: P1,
+    ppi_idle: P2,
+    buffers: [&'static mut [u8]; 2],
+    // Index of the buffer the DMA is currently writing to.
+    active: usize,
+    // The DMA has started on `active`, so RXD.PTR can be pointed at the other buffer.
+    next_pending: bool,
+}
+
+#[cfg(not(feature = "9160"))]
+impl<T, I, P1, P2> RxStreamInner<T, I, P1, P2>
+where
+    T: Instance,
+    I: timer::Instance,
+    P1: ConfigurablePpi,
+    P2: ConfigurablePpi,
+{
+    /// Stops the idle detection and the reception, discarding data not yet handed out.
+    fn halt(&mut self) {
+        self.ppi_byte.disable();
+        self.ppi_idle.disable();
+        self.timer.timer_cancel();
+
+        let uarte = self.rx.uarte();
+        uarte.shorts.reset();
+        uarte
+            .intenclr
+            .write(|w| w.endrx().clear().rxstarted().clear());
+
+        uarte.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+        while uarte.events_rxto.read().bits() == 0 {}
+        uarte.events_rxto.reset();
+        uarte.events_endrx.reset();
+        uarte.events_rxstarted.reset();
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed.
+        compiler_fence(SeqCst);
+    }
+}
+
+#[cfg(not(feature = "9160"))]
+impl<T, I, P1, P2> UarteRxStream<T, I, P1, P2>
+where
+    T: Instance,
+    I: timer::Instance,
+    P1: ConfigurablePpi,
+    P2: ConfigurablePpi,
+{
+    #[inline(always)]
+    fn inner(&mut self) -> &mut RxStreamInner<T, I, P1, P2> {
+        self.inner
+            .as_mut()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() })
+    }
+
+    /// Enables the `ENDRX` and `RXSTARTED` interrupts that drive `process`.
+    ///
+    /// Note that the interrupt also has to be unmasked in the NVIC, or the
+    /// handler won't get called.
+    pub fn enable_interrupt(&mut self) {
+        self.inner()
+            .rx
+            .uarte()
+            .intenset
+            .write(|w| w.endrx().set().rxstarted().set());
+    }
+
+    /// Disables the `ENDRX` and `RXSTARTED` interrupts.
+    pub fn disable_interrupt(&mut self) {
+        self.inner()
+            .rx
+            .uarte()
+            .intenclr
+            .write(|w| w.endrx().clear().rxstarted().clear());
+    }
+
+    /// Handles pending stream events, calling `f` with the received data if a buffer has been
+    /// completed, either because it is full or because the line went idle.
+    ///
+    /// Returns `true` if `f` has been called. This should be called from the UARTE interrupt
+    /// handler, or polled. The other buffer is being filled while `f` runs; it must return
+    /// before that buffer is full too, otherwise data gets overwritten.
+    pub fn process<F>(&mut self, f: F) -> bool
+    where
+        F: FnOnce(&[u8]),
+    {
+        let inner = self.inner();
+        let uarte = inner.rx.uarte();
+
+        // Generated after an idle-triggered STOPRX; reception has already been
+        // restarted by the shortcut.
+        uarte.events_rxto.reset();
+
+        if uarte.events_rxstarted.read().bits() != 0 {
+            uarte.events_rxstarted.reset();
+            inner.next_pending = true;
+        }
+
+        let mut completed = false;
+        if uarte.events_endrx.read().bits() != 0 {
+            uarte.events_endrx.reset();
+            let amount = uarte.rxd.amount.read().bits() as usize;
+
+            // Conservative compiler fence to prevent optimizations that do not
+            // take in to account actions by DMA. The fence has been placed here,
+            // after all DMA actions on the completed buffer have finished.
+            compiler_fence(SeqCst);
+
+            let done = inner.active;
+            inner.active ^= 1;
+            f(&inner.buffers[done][..amount]);
+            completed = true;
+        }
+
+        if inner.next_pending {
+            // The buffer not in use by the DMA has been handed out and released,
+            // so it can be queued for the next reception.
+            let next = &inner.buffers[inner.active ^ 1];
+            uarte
+                .rxd
+                .ptr
+                .write(|w| unsafe { w.ptr().bits(next.as_ptr() as u32) });
+            uarte
+                .rxd
+                .maxcnt
+                .write(|w| unsafe { w.maxcnt().bits(next.len() as _) });
+            inner.next_pending = false;
+        }
+
+        completed
+    }
+
+    /// Stops the stream, returning the receiver, timer, PPI channels and buffers.
+    ///
+    /// Data that has not yet been handed out by `process` is discarded.
+    pub fn stop(mut self) -> (UarteRx<T>, Timer<I>, P1, P2, [&'static mut [u8]; 2]) {
+        let mut inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.halt();
+
+        (
+            inner.rx,
+            Timer::one_shot(inner.timer),
+            inner.ppi_byte,
+            inner.ppi_idle,
+            inner.buffers,
+        )
+    }
+}
+
+#[cfg(not(feature = "9160"))]
+impl<T, I, P1, P2> Drop for UarteRxStream<T, I, P1, P2>
+where
+    T: Instance,
+    I: timer::Instance,
+    P1: ConfigurablePpi,
+    P2: ConfigurablePpi,
+{
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            inner.halt();
+        }
+    }
+}
+
+/// An ongoing EasyDMA transmission started by `UarteTx::write_dma`.
+pub struct TxTransfer<T: Instance, B> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<TxInner<T, B>>,
+}
+
+struct TxInner<T: Instance, B> {
+    buffer: B,
+    tx: UarteTx<T>,
+}
+
+impl<T: Instance, B> TxTransfer<T, B> {
+    /// Checks if the transmission has ended (non-blocking).
+    ///
+    /// Typically called from the UARTE interrupt handler before calling `wait`.
+    #[inline(always)]
+    pub fn is_done(&self) -> bool {
+        let inner = self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.tx.uarte.events_endtx.read().bits() != 0
+    }
+
+    /// Blocks until the transmission has ended, then returns the buffer and the transmitter.
+    pub fn wait(mut self) -> (B, UarteTx<T>) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        let uarte = &inner.tx.uarte;
+
+        while uarte.events_endtx.read().bits() == 0 {}
+        uarte.events_endtx.reset();
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed.
+        compiler_fence(SeqCst);
+
+        // Lower power consumption by disabling the transmitter once we're
+        // finished.
+        uarte.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+        while uarte.events_txstopped.read().bits() == 0 {}
+        uarte.events_txstopped.reset();
+
+        (inner.buffer, inner.tx)
+    }
+}
+
+impl<T: Instance, B> Drop for TxTransfer<T, B> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            let uarte = &inner.tx.uarte;
+            uarte.tasks_stoptx.write(|w| unsafe { w.bits(1) });
+            while uarte.events_txstopped.read().bits() == 0 {}
+            uarte.events_txstopped.reset();
+            uarte.events_endtx.reset();
+            compiler_fence(SeqCst);
+        }
+    }
+}
+
+/// An ongoing EasyDMA reception started by `UarteRx::read_dma`.
+pub struct RxTransfer<T: Instance, B> {
+    // FIXME: Always `Some`, only using `Option` here to allow moving fields out of `inner`.
+    inner: Option<RxInner<T, B>>,
+}
+
+struct RxInner<T: Instance, B> {
+    buffer: B,
+    ptr: u32,
+    len: usize,
+    rx: UarteRx<T>,
+}
+
+impl<T: Instance, B> RxTransfer<T, B> {
+    /// Checks if the receive buffer has been filled (non-blocking).
+    ///
+    /// Typically called from the UARTE interrupt handler before calling `wait`.
+    #[inline(always)]
+    pub fn is_done(&self) -> bool {
+        let inner = self
+            .inner
+            .as_ref()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        inner.rx.uarte().events_endrx.read().bits() != 0
+    }
+
+    /// Blocks until the receive buffer has been filled, then returns the buffer and the receiver.
+    pub fn wait(mut self) -> (B, UarteRx<T>) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        let uarte = inner.rx.uarte();
+
+        while uarte.events_endrx.read().bits() == 0 {}
+        uarte.events_endrx.reset();
+
+        // Conservative compiler fence to prevent optimizations that do not
+        // take in to account actions by DMA. The fence has been placed here,
+        // after all possible DMA actions have completed.
+        compiler_fence(SeqCst);
+
+        (inner.buffer, inner.rx)
+    }
+
+    /// Stops the reception early and returns the buffer, the receiver and the number of bytes
+    /// that were received.
+    ///
+    /// Bytes still held in the UARTE's RX FIFO are flushed into the buffer as well.
+    pub fn cancel(mut self) -> (B, UarteRx<T>, usize) {
+        let inner = self
+            .inner
+            .take()
+            .unwrap_or_else(|| unsafe { core::hint::unreachable_unchecked() });
+        let amount = stop_read(inner.rx.uarte(), inner.ptr, inner.len);
+        (inner.buffer, inner.rx, amount)
+    }
+}
+
+impl<T: Instance, B> Drop for RxTransfer<T, B> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.as_mut() {
+            stop_read(inner.rx.uarte(), inner.ptr, inner.len);
+        }
+    }
+}
+
+/// Stops an ongoing reception into the buffer at `ptr`, flushing the RX FIFO
+/// into the remaining space. Returns the total number of bytes received.
+fn stop_read(uarte: &uarte0::RegisterBlock, ptr: u32, len: usize) -> usize {
+    if uarte.events_endrx.read().bits() != 0 {
+        // The buffer has already been filled.
+        uarte.events_endrx.reset();
+        compiler_fence(SeqCst);
+        return uarte.rxd.amount.read().bits() as usize;
+    }
+
+    uarte.tasks_stoprx.write(|w| unsafe { w.bits(1) });
+    while uarte.events_rxto.read().bits() == 0 {}
+    uarte.events_rxto.reset();
+    uarte.events_endrx.reset();
+
+    let mut amount = uarte.rxd.amount.read().bits() as usize;
+
+    if amount < len {
+        // Flush the FIFO into the unused remainder of the buffer.
+        uarte
+            .rxd
+            .ptr
+            .write(|w| unsafe { w.ptr().bits(ptr + amount as u32) });
+        uarte
+            .rxd
+            .maxcnt
+            .write(|w| unsafe { w.maxcnt().bits((len - amount) as _) });
+        uarte.tasks_flushrx.write(|w| unsafe { w.bits(1) });
+        while uarte.events_endrx.read().bits() == 0 {}
+        uarte.events_endrx.reset();
+        amount += uarte.rxd.amount.read().bits() as usize;
+    }
+
+    // Conservative compiler fence to prevent optimizations that do not
+    // take in to account actions by DMA. The fence has been placed here,
+    // after all possible DMA actions have completed.
+    compiler_fence(SeqCst);
+
+    amount
+}
+
 pub struct Pins {
     pub rxd: Pin<Input<Floating>>,
     pub txd: Pin<Output<PushPull>>,
@@ -449,18 +1255,68 @@ pub enum Error {
     BufferNotInRAM,
 }
 
-pub trait Instance: Deref<Target = uarte0::RegisterBlock> + sealed::Sealed {}
+#[cfg(feature = "embedded-hal-1")]
+impl embedded_io::Error for Error {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            Error::Timeout(_) => embedded_io::ErrorKind::TimedOut,
+            _ => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+/// Implemented by all UARTE instances.
+pub trait Instance: Deref<Target = uarte0::RegisterBlock> + sealed::Sealed {
+    /// The interrupt associated with this UARTE instance.
+    const INTERRUPT: Interrupt;
+
+    /// Returns a pointer to the instance's register block.
+    fn ptr() -> *const uarte0::RegisterBlock;
+}
 
 mod sealed {
-    pub trait Sealed {}
+    pub trait Sealed {
+        #[cfg(feature = "async")]
+        fn waker() -> &'static crate::asynch::AtomicWaker;
+    }
 }
 
-impl sealed::Sealed for UARTE0 {}
-impl Instance for UARTE0 {}
+impl sealed::Sealed for UARTE0 {
+    #[cfg(feature = "async")]
+    fn waker() -> &'static crate::asynch::AtomicWaker {
+        static WAKER: crate::asynch::AtomicWaker = crate::asynch::AtomicWaker::new();
+        &WAKER
+    }
+}
+impl Instance for UARTE0 {
+    #[cfg(not(feature = "9160"))]
+    const INTERRUPT: Interrupt = Interrupt::UARTE0_UART0;
+    #[cfg(feature = "9160")]
+    const INTERRUPT: Interrupt = Interrupt::UARTE0_SPIM0_SPIS0_TWIM0_TWIS0;
+
+    fn ptr() -> *const uarte0::RegisterBlock {
+        UARTE0::ptr()
+    }
+}
 
 #[cfg(any(feature = "52833", feature = "52840", feature = "9160"))]
 mod _uarte1 {
     use super::*;
-    impl sealed::Sealed for UARTE1 {}
-    impl Instance for UARTE1 {}
+    impl sealed::Sealed for UARTE1 {
+        #[cfg(feature = "async")]
+        fn waker() -> &'static crate::asynch::AtomicWaker {
+            static WAKER: crate::asynch::AtomicWaker = crate::asynch::AtomicWaker::new();
+            &WAKER
+        }
+    }
+    impl Instance for UARTE1 {
+        #[cfg(not(feature = "9160"))]
+        const INTERRUPT: Interrupt = Interrupt::UARTE1;
+        #[cfg(feature = "9160")]
+        const INTERRUPT: Interrupt = Interrupt::UARTE1_SPIM1_SPIS1_TWIM1_TWIS1;
+
+        fn ptr() -> *const uarte0::RegisterBlock {
+            UARTE1::ptr()
+        }
+    }
 }
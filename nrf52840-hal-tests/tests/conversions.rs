@@ -0,0 +1,54 @@
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use nrf52840_hal as _;
+use panic_probe as _;
+
+#[defmt_test::tests]
+mod tests {
+    use defmt::{assert, assert_eq};
+    use nrf52840_hal::{
+        i2s::{MckFreq, Ratio, SampleRate, SampleWidth},
+        saadc::{Gain, Reference, Resolution, SaadcConfig},
+    };
+
+    #[test]
+    fn sample_rate_48khz_16bit() {
+        let rate = SampleRate::closest(48_000, SampleWidth::_16bit);
+        assert!(rate.mck_freq == MckFreq::_32MDiv21);
+        assert!(rate.ratio == Ratio::_32x);
+        assert_eq!(rate.actual_hz, 47_619);
+        assert_eq!(rate.error_ppm, -7_936);
+    }
+
+    #[test]
+    fn sample_rate_24bit_needs_a_multiple_of_48() {
+        let rate = SampleRate::closest(16_000, SampleWidth::_24bit);
+        assert!(rate.mck_freq == MckFreq::_32MDiv42);
+        assert!(rate.ratio == Ratio::_48x);
+        assert_eq!(rate.actual_hz, 15_873);
+    }
+
+    #[test]
+    fn millivolts_vdd_reference() {
+        // VDD/4 reference and 1/4 gain: the full scale is VDD
+        let config = SaadcConfig::default();
+        assert_eq!(config.to_millivolts(0, 3_000), 0);
+        assert_eq!(config.to_millivolts(1 << 13, 3_000), 1_500);
+        assert_eq!(config.to_millivolts(1 << 14, 3_000), 3_000);
+    }
+
+    #[test]
+    fn millivolts_internal_reference() {
+        // 0.6 V reference and 1/6 gain: the full scale is 3.6 V
+        let config = SaadcConfig {
+            resolution: Resolution::_12BIT,
+            reference: Reference::INTERNAL,
+            gain: Gain::GAIN1_6,
+            ..SaadcConfig::default()
+        };
+        assert_eq!(config.to_millivolts(1 << 11, 0), 1_800);
+        assert_eq!(config.to_millivolts(-(1 << 11), 0), -1_800);
+    }
+}
@@ -0,0 +1,126 @@
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use nrf52840_hal as _;
+use panic_probe as _;
+
+#[defmt_test::tests]
+mod tests {
+    use defmt::{assert, assert_eq, unwrap};
+    use nrf52840_hal::ieee802154::{
+        frame::{Address, Frame, FrameBuilder, FrameError, FrameType, KeyId, SecurityHeader},
+        Packet,
+    };
+
+    const PAN_ID: u16 = 0xABCD;
+    const SHORT: Address = Address::Short(0x1234);
+    const EXTENDED: Address = Address::Extended(0x0011_2233_4455_6677);
+
+    #[test]
+    fn data_frame_round_trip() {
+        let mut packet = Packet::new();
+        let builder = FrameBuilder::data(7)
+            .dst(PAN_ID, SHORT)
+            .src(PAN_ID, EXTENDED)
+            .ack_request(true);
+        assert!(builder.build(&mut packet, &[1, 2, 3]).is_ok());
+
+        let frame = unwrap!(Frame::parse(&packet).ok());
+        assert!(frame.frame_type() == FrameType::Data);
+        assert!(frame.frame_control().ack_request());
+        // equal PAN IDs are compressed
+        assert!(frame.frame_control().pan_id_compression());
+        assert_eq!(frame.sequence_number(), Some(7));
+        assert_eq!(frame.dst_pan_id(), Some(PAN_ID));
+        assert!(frame.dst_address() == Some(SHORT));
+        assert_eq!(frame.src_pan_id(), Some(PAN_ID));
+        assert!(frame.src_address() == Some(EXTENDED));
+        assert!(frame.security_header().is_none());
+        assert_eq!(frame.header_len(), 3 + 2 + 2 + 8);
+        assert_eq!(frame.payload(), &[1, 2, 3][..]);
+        assert!(frame.mic().is_empty());
+    }
+
+    #[test]
+    fn distinct_pan_ids_round_trip() {
+        let mut packet = Packet::new();
+        let builder = FrameBuilder::mac_command(0)
+            .dst(PAN_ID, Address::BROADCAST)
+            .src(0x0001, SHORT);
+        assert!(builder.build(&mut packet, &[0x04]).is_ok());
+
+        let frame = unwrap!(Frame::parse(&packet).ok());
+        assert!(frame.frame_type() == FrameType::MacCommand);
+        assert!(!frame.frame_control().pan_id_compression());
+        assert_eq!(frame.dst_pan_id(), Some(PAN_ID));
+        assert!(frame.dst_address() == Some(Address::BROADCAST));
+        assert_eq!(frame.src_pan_id(), Some(0x0001));
+        assert!(frame.src_address() == Some(SHORT));
+        assert_eq!(frame.payload(), &[0x04][..]);
+    }
+
+    #[test]
+    fn ack_round_trip() {
+        let mut packet = Packet::new();
+        assert!(FrameBuilder::ack(42)
+            .frame_pending(true)
+            .build(&mut packet, &[])
+            .is_ok());
+        assert_eq!(packet.len(), 3);
+
+        let frame = unwrap!(Frame::parse(&packet).ok());
+        assert!(frame.frame_type() == FrameType::Ack);
+        assert!(frame.frame_control().frame_pending());
+        assert_eq!(frame.sequence_number(), Some(42));
+        assert!(frame.dst_address().is_none());
+        assert!(frame.src_address().is_none());
+        assert!(frame.payload().is_empty());
+    }
+
+    #[test]
+    fn secured_frame_round_trip() {
+        // ENC-MIC-32: the payload is followed by a 4-byte MIC
+        let security = SecurityHeader {
+            level: 5,
+            key_id: KeyId::Source4 {
+                source: 0x0102_0304,
+                index: 9,
+            },
+            frame_counter: Some(0x0A0B_0C0D),
+        };
+        let mut packet = Packet::new();
+        let builder = FrameBuilder::data(1)
+            .dst(PAN_ID, SHORT)
+            .src(PAN_ID, SHORT)
+            .security(security);
+        assert!(builder
+            .build(&mut packet, &[1, 2, 3, 0xF0, 0xF1, 0xF2, 0xF3])
+            .is_ok());
+
+        let frame = unwrap!(Frame::parse(&packet).ok());
+        assert!(frame.frame_control().security_enabled());
+        assert!(frame.security_header() == Some(security));
+        assert_eq!(frame.payload(), &[1, 2, 3][..]);
+        assert_eq!(frame.mic(), &[0xF0, 0xF1, 0xF2, 0xF3][..]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        // truncated in the destination address
+        assert!(
+            Frame::parse(&[0x41, 0x88, 0x00, 0xCD, 0xAB, 0x34]).err() == Some(FrameError::TooShort)
+        );
+        // multipurpose frame
+        assert!(Frame::parse(&[0x05, 0x00, 0x00]).err() == Some(FrameError::UnsupportedFrameType));
+    }
+
+    #[test]
+    fn build_too_long() {
+        let mut packet = Packet::new();
+        let payload = [0; Packet::CAPACITY as usize];
+        assert!(
+            FrameBuilder::data(0).build(&mut packet, &payload).err() == Some(FrameError::TooLong)
+        );
+    }
+}
@@ -17,19 +17,20 @@ use nrf52840_hal::{
 };
 
 struct State {
-    _uarte: Uarte<UARTE0>,
+    // `None` while split by a test
+    uarte: Option<Uarte<UARTE0>>,
     _timer: Timer<TIMER0, OneShot>,
 }
 
 #[defmt_test::tests]
 mod tests {
-    use defmt::unwrap;
+    use defmt::{assert_eq, unwrap};
     use nrf52840_hal::{
         gpio::{p0, Level},
         pac,
     };
     use nrf52840_hal::{
-        uarte::{Baudrate, Parity, Pins, Uarte},
+        uarte::{Baudrate, Error, Parity, Pins, Uarte},
         Timer,
     };
 
@@ -52,14 +53,46 @@ mod tests {
             rts: None,
         };
 
-        let _uarte = Uarte::new(p.UARTE0, pins, Parity::EXCLUDED, Baudrate::BAUD9600);
+        let uarte = Uarte::new(p.UARTE0, pins, Parity::EXCLUDED, Baudrate::BAUD9600);
 
         State {
-            _uarte,
+            uarte: Some(uarte),
             _timer,
         }
     }
 
+    #[test]
+    fn split_loopback(state: &mut State) {
+        const BYTES: [u8; 4] = [0x42, 0x43, 0x44, 0x45];
+
+        let (tx, rx) = unwrap!(state.uarte.take()).split();
+        let tx_buffer = unwrap!(cortex_m::singleton!(: [u8; 4] = BYTES));
+        let rx_buffer = unwrap!(cortex_m::singleton!(: [u8; 4] = [0; 4]));
+
+        // the reception has to be running before the first byte goes out
+        let rx_transfer = unwrap!(rx.read_dma(rx_buffer).ok());
+        let tx_transfer = unwrap!(tx.write_dma(tx_buffer).ok());
+
+        let (_, tx) = tx_transfer.wait();
+        let (rx_buffer, rx) = rx_transfer.wait();
+        assert_eq!(&rx_buffer[..], &BYTES[..]);
+
+        state.uarte = Some(Uarte::join(tx, rx));
+    }
+
+    #[test]
+    fn write_dma_rejects_buffer_in_flash(state: &mut State) {
+        static BYTES: [u8; 4] = [0x42, 0x43, 0x44, 0x45];
+
+        let (tx, rx) = unwrap!(state.uarte.take()).split();
+        let tx = match tx.write_dma(&BYTES) {
+            Err((Error::BufferNotInRAM, tx, _)) => tx,
+            _ => defmt::panic!("`write_dma` accepted a buffer located in Flash"),
+        };
+
+        state.uarte = Some(Uarte::join(tx, rx));
+    }
+
     // won't work because of how the `read` API work
     /*
     #[test]
@@ -0,0 +1,172 @@
+// Required connections:
+//
+// - P0.27 <-> P0.30 (SCL)
+// - P0.26 <-> P0.31 (SDA)
+//
+// The TWIS enables the internal pull-ups of its pins, which is enough for 100 kHz.
+
+#![no_std]
+#![no_main]
+
+use defmt_rtt as _;
+use nrf52840_hal as _;
+use panic_probe as _;
+
+use core::sync::atomic::{compiler_fence, Ordering::SeqCst};
+use nrf52840_hal::{
+    pac::{TWIM0, TWIS1},
+    twim::Twim,
+};
+
+const ADDRESS: u8 = 0x42;
+
+struct State {
+    twim: Twim<TWIM0>,
+    // driven through its registers to answer with pre-prepared buffers
+    twis: TWIS1,
+}
+
+/// Prepares the TWIS to store the bytes written by the master into `rx`, and to answer reads
+/// with `tx`
+fn prepare_slave(twis: &TWIS1, rx: &mut [u8], tx: &[u8]) {
+    compiler_fence(SeqCst);
+    twis.rxd
+        .ptr
+        .write(|w| unsafe { w.ptr().bits(rx.as_mut_ptr() as u32) });
+    twis.rxd
+        .maxcnt
+        .write(|w| unsafe { w.bits(rx.len() as u32) });
+    twis.txd
+        .ptr
+        .write(|w| unsafe { w.ptr().bits(tx.as_ptr() as u32) });
+    twis.txd
+        .maxcnt
+        .write(|w| unsafe { w.bits(tx.len() as u32) });
+    twis.events_stopped.reset();
+    twis.tasks_preparerx.write(|w| unsafe { w.bits(1) });
+    twis.tasks_preparetx.write(|w| unsafe { w.bits(1) });
+}
+
+/// Waits for the end of the transaction on the TWIS side and returns the number of bytes
+/// received and transmitted
+fn wait_slave(twis: &TWIS1) -> (u32, u32) {
+    while twis.events_stopped.read().bits() == 0 {}
+    twis.events_stopped.reset();
+    compiler_fence(SeqCst);
+    (twis.rxd.amount.read().bits(), twis.txd.amount.read().bits())
+}
+
+#[defmt_test::tests]
+mod tests {
+    use defmt::{assert, assert_eq, unwrap};
+    use eh1::i2c::{Error as _, ErrorKind};
+    use nrf52840_hal::{
+        gpio::p0,
+        pac,
+        twim::{self, Frequency, Operation, Twim},
+        twis::{self, Twis},
+    };
+
+    use super::{prepare_slave, wait_slave, State, ADDRESS};
+
+    #[init]
+    fn init() -> State {
+        let p = unwrap!(pac::Peripherals::take());
+        let port0 = p0::Parts::new(p.P0);
+
+        let pins = twim::Pins {
+            scl: port0.p0_27.into_floating_input().degrade(),
+            sda: port0.p0_26.into_floating_input().degrade(),
+        };
+        let twim = Twim::new(p.TWIM0, pins, Frequency::K100);
+
+        let pins = twis::Pins {
+            scl: port0.p0_30.into_floating_input().degrade(),
+            sda: port0.p0_31.into_floating_input().degrade(),
+        };
+        let twis = Twis::new(p.TWIS1, pins, ADDRESS);
+        twis.enable();
+
+        State {
+            twim,
+            twis: twis.free(),
+        }
+    }
+
+    #[test]
+    fn adjacent_writes_are_merged(state: &mut State) {
+        let mut rx = [0; 8];
+        prepare_slave(&state.twis, &mut rx, &[]);
+
+        let result = state.twim.transaction(
+            ADDRESS,
+            &mut [Operation::Write(&[1]), Operation::Write(&[2, 3])],
+        );
+        assert!(result.is_ok());
+
+        // a single write of 3 bytes, without a repeated start in between
+        assert!(wait_slave(&state.twis) == (3, 0));
+        assert_eq!(&rx[..3], &[1, 2, 3][..]);
+    }
+
+    #[test]
+    fn adjacent_reads_are_merged(state: &mut State) {
+        let tx = [4, 5, 6, 7];
+        prepare_slave(&state.twis, &mut [], &tx);
+
+        let mut first = [0; 1];
+        let mut second = [0; 3];
+        let result = state.twim.transaction(
+            ADDRESS,
+            &mut [Operation::Read(&mut first), Operation::Read(&mut second)],
+        );
+        assert!(result.is_ok());
+
+        assert!(wait_slave(&state.twis) == (0, 4));
+        assert_eq!(&first[..], &[4][..]);
+        assert_eq!(&second[..], &[5, 6, 7][..]);
+    }
+
+    #[test]
+    fn writes_then_reads(state: &mut State) {
+        let mut rx = [0; 8];
+        let tx = [4, 5, 6, 7];
+        prepare_slave(&state.twis, &mut rx, &tx);
+
+        let mut first = [0; 2];
+        let mut second = [0; 2];
+        let result = state.twim.transaction(
+            ADDRESS,
+            &mut [
+                Operation::Write(&[1]),
+                Operation::Write(&[2]),
+                Operation::Read(&mut first),
+                Operation::Read(&mut second),
+            ],
+        );
+        assert!(result.is_ok());
+
+        assert!(wait_slave(&state.twis) == (2, 4));
+        assert_eq!(&rx[..2], &[1, 2][..]);
+        assert_eq!(&first[..], &[4, 5][..]);
+        assert_eq!(&second[..], &[6, 7][..]);
+    }
+
+    #[test]
+    fn address_nack(state: &mut State) {
+        let mut buffer = [0; 1];
+        let result = state
+            .twim
+            .transaction(ADDRESS + 1, &mut [Operation::Read(&mut buffer)]);
+        assert!(result == Err(twim::Error::AddressNack));
+    }
+
+    #[test]
+    fn error_kinds() {
+        assert!(twim::Error::Overrun.kind() == ErrorKind::Overrun);
+        assert!(matches!(
+            twim::Error::DataNack.kind(),
+            ErrorKind::NoAcknowledge(_)
+        ));
+    }
+}